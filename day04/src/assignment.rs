@@ -0,0 +1,208 @@
+use std::str::FromStr;
+
+use crate::{
+    coverage::{CoverageIndex, IntervalSet},
+    interval::Interval,
+    parser::{AssignmentParser, ParseError},
+};
+
+pub type Assignment = Interval<u32>;
+
+pub struct AssignmentRow {
+    pub assignments: Vec<Assignment>,
+}
+
+impl AssignmentRow {
+    /// Index pairs `(i, j)` with `i < j`.
+    pub(crate) fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let len = self.assignments.len();
+        (0..len).flat_map(move |i| (i + 1..len).map(move |j| (i, j)))
+    }
+
+    fn is_containing(&self, (i, j): (usize, usize)) -> bool {
+        self.assignments[i].contains(&self.assignments[j])
+            || self.assignments[j].contains(&self.assignments[i])
+    }
+
+    fn is_overlapping(&self, (i, j): (usize, usize)) -> bool {
+        self.assignments[i].overlap(&self.assignments[j])
+    }
+
+    /// Pairs `(i, j)` where one of the two assignments contains the other.
+    pub fn containing_pairs(&self) -> Vec<(usize, usize)> {
+        self.pairs().filter(|&p| self.is_containing(p)).collect()
+    }
+
+    pub fn overlapping_pairs(&self) -> Vec<(usize, usize)> {
+        self.pairs().filter(|&p| self.is_overlapping(p)).collect()
+    }
+
+    /// True if any assignment is fully contained in another one.
+    pub fn complete_overlap(&self) -> bool {
+        self.pairs().any(|p| self.is_containing(p))
+    }
+
+    /// True if any two assignments share at least one section.
+    pub fn overlap(&self) -> bool {
+        self.pairs().any(|p| self.is_overlapping(p))
+    }
+
+    /// Sections shared by every assignment of the row.
+    pub fn common_sections(&self) -> Option<Assignment> {
+        let (first, rest) = self.assignments.split_first()?;
+
+        rest.iter().try_fold(*first, |acc, a| acc.intersection(a))
+    }
+
+    pub fn all_overlap(&self) -> bool {
+        self.common_sections().is_some()
+    }
+}
+
+impl AssignmentRow {
+    pub fn parse_bytes(line: &[u8]) -> Result<Self, ParseError> {
+        Ok(AssignmentRow {
+            assignments: AssignmentParser::new(line).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl FromStr for AssignmentRow {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_bytes(s.as_bytes())
+    }
+}
+
+impl TryFrom<String> for AssignmentRow {
+    fn try_from(value: String) -> Result<Self, ParseError> {
+        value.parse()
+    }
+
+    type Error = ParseError;
+}
+
+/// All the rows of a file, indexed for camp-wide coverage queries.
+pub struct Camp {
+    pub rows: Vec<AssignmentRow>,
+    index: CoverageIndex<u32>,
+}
+
+impl Camp {
+    pub fn new(rows: Vec<AssignmentRow>) -> Self {
+        let index = CoverageIndex::new(
+            rows.iter()
+                .enumerate()
+                .flat_map(|(idx, row)| row.assignments.iter().map(move |a| (*a, idx))),
+        );
+
+        Camp { rows, index }
+    }
+
+    pub fn coverage(&self) -> IntervalSet<u32> {
+        self.index.coverage()
+    }
+
+    /// Sections between the lowest and highest assigned one that nobody cleans.
+    pub fn uncovered(&self) -> Vec<Assignment> {
+        match self.index.span() {
+            Some(span) => self.coverage().gaps_within(span),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn covered_by_more_than(&self, k: usize) -> IntervalSet<u32> {
+        self.index.covered_more_than(k)
+    }
+
+    /// Indexes of the other rows sharing at least one section with row `idx`.
+    pub fn rows_overlapping(&self, idx: usize) -> Vec<usize> {
+        let Some(row) = self.rows.get(idx) else {
+            return Vec::new();
+        };
+
+        let mut res: Vec<usize> = row
+            .assignments
+            .iter()
+            .flat_map(|a| self.index.overlapping(a))
+            .filter(|&other| other != idx)
+            .collect();
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::assignment::{Assignment, AssignmentRow, Camp};
+
+    #[test]
+    fn two_elves_rows() {
+        let row = AssignmentRow::try_from("2-8,3-7".to_string()).unwrap();
+        assert!(row.complete_overlap());
+        assert!(row.overlap());
+
+        let row = AssignmentRow::try_from("5-7,7-9".to_string()).unwrap();
+        assert!(!row.complete_overlap());
+        assert!(row.overlap());
+
+        let row = AssignmentRow::try_from("2-4,6-8".to_string()).unwrap();
+        assert!(!row.overlap());
+    }
+
+    #[test]
+    fn group_rows() {
+        let row = AssignmentRow::try_from("1-5,4-9,3-4".to_string()).unwrap();
+
+        assert_eq!(row.assignments.len(), 3);
+        assert_eq!(row.containing_pairs(), vec![(0, 2)]);
+        assert_eq!(row.overlapping_pairs(), vec![(0, 1), (0, 2), (1, 2)]);
+        assert_eq!(row.common_sections(), Some(Assignment::new(4, 4)));
+
+        let row = AssignmentRow::try_from("1-3,2-5,4-6".to_string()).unwrap();
+        assert!(row.overlap());
+        assert!(!row.all_overlap());
+    }
+
+    fn camp() -> Camp {
+        Camp::new(
+            ["2-4,6-8", "3-5,10-12", "7-7,20-21"]
+                .iter()
+                .map(|row| row.parse().unwrap())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn camp_uncovered() {
+        assert_eq!(
+            camp().uncovered(),
+            [Assignment::new(9, 9), Assignment::new(13, 19)]
+        );
+        assert_eq!(Camp::new(Vec::new()).uncovered(), []);
+    }
+
+    #[test]
+    fn camp_covered_by_more_than() {
+        let camp = camp();
+
+        assert_eq!(
+            camp.covered_by_more_than(1).ranges(),
+            [Assignment::new(3, 4), Assignment::new(7, 7)]
+        );
+        assert_eq!(camp.covered_by_more_than(0), camp.coverage());
+        assert!(camp.covered_by_more_than(2).is_empty());
+    }
+
+    #[test]
+    fn camp_rows_overlapping() {
+        let camp = camp();
+
+        assert_eq!(camp.rows_overlapping(0), [1, 2]);
+        assert_eq!(camp.rows_overlapping(1), [0]);
+        assert_eq!(camp.rows_overlapping(2), [0]);
+        assert_eq!(camp.rows_overlapping(3), []);
+    }
+}
@@ -0,0 +1,343 @@
+use crate::interval::{Discrete, Interval};
+
+/// Sorted set of disjoint, non-adjacent intervals.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Interval<T>>,
+}
+
+impl<T: Discrete> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { ranges: Vec::new() }
+    }
+
+    pub fn ranges(&self) -> &[Interval<T>] {
+        &self.ranges
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        let start = self
+            .ranges
+            .partition_point(|r| r.to < interval.from && !r.is_adjacent(&interval));
+        let end = self
+            .ranges
+            .partition_point(|r| r.from <= interval.to || r.is_adjacent(&interval));
+
+        let mut merged = interval;
+        if start < end {
+            merged.from = merged.from.min(self.ranges[start].from);
+            merged.to = merged.to.max(self.ranges[end - 1].to);
+        }
+
+        self.ranges.splice(start..end, [merged]);
+    }
+
+    pub fn contains_point(&self, point: T) -> bool {
+        let idx = self.ranges.partition_point(|r| r.to < point);
+
+        self.ranges
+            .get(idx)
+            .is_some_and(|r| r.contains_point(point))
+    }
+
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().map(|r| r.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Sections of `bounds` that are not in the set.
+    pub fn gaps_within(&self, bounds: Interval<T>) -> Vec<Interval<T>> {
+        let mut res = Vec::new();
+        let mut rest = Some(bounds);
+
+        for range in &self.ranges {
+            let Some(current) = rest else {
+                break;
+            };
+
+            let mut pieces = current.difference(range).into_iter();
+            match (pieces.next(), pieces.next()) {
+                (Some(before), Some(after)) => {
+                    res.push(before);
+                    rest = Some(after);
+                }
+                (Some(piece), None) if piece.to < range.from => {
+                    res.push(piece);
+                    rest = None;
+                }
+                (piece, _) => rest = piece,
+            }
+        }
+
+        res.extend(rest);
+        res
+    }
+}
+
+impl<T: Discrete> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Discrete> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut sorted: Vec<Interval<T>> = iter.into_iter().filter(|i| !i.is_empty()).collect();
+        sorted.sort_by_key(|i| i.from);
+
+        let mut ranges: Vec<Interval<T>> = Vec::with_capacity(sorted.len());
+        for interval in sorted {
+            match ranges
+                .last_mut()
+                .and_then(|last| last.union(&interval).map(|u| (last, u)))
+            {
+                Some((last, union)) => *last = union,
+                None => ranges.push(interval),
+            }
+        }
+
+        IntervalSet { ranges }
+    }
+}
+
+/// Static index over a collection of tagged intervals.
+///
+/// Entries are kept sorted by start, with an implicit balanced tree on top
+/// of the array that stores the maximum end of each subtree, so overlap
+/// queries run in O(log n + k).
+#[derive(Debug, Clone)]
+pub struct CoverageIndex<T> {
+    entries: Vec<(Interval<T>, usize)>,
+    max_to: Vec<T>,
+}
+
+impl<T: Discrete> CoverageIndex<T> {
+    pub fn new(intervals: impl IntoIterator<Item = (Interval<T>, usize)>) -> Self {
+        let mut entries: Vec<(Interval<T>, usize)> = intervals
+            .into_iter()
+            .filter(|(i, _)| !i.is_empty())
+            .collect();
+        entries.sort_by_key(|(i, _)| (i.from, i.to));
+
+        let mut max_to: Vec<T> = entries.iter().map(|(i, _)| i.to).collect();
+        Self::build(&entries, &mut max_to, 0, entries.len());
+
+        CoverageIndex { entries, max_to }
+    }
+
+    fn build(
+        entries: &[(Interval<T>, usize)],
+        max_to: &mut [T],
+        lo: usize,
+        hi: usize,
+    ) -> Option<T> {
+        if lo >= hi {
+            return None;
+        }
+
+        let mid = (lo + hi) / 2;
+        let mut res = entries[mid].0.to;
+        for child in [
+            Self::build(entries, max_to, lo, mid),
+            Self::build(entries, max_to, mid + 1, hi),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            res = res.max(child);
+        }
+
+        max_to[mid] = res;
+        Some(res)
+    }
+
+    /// Tags of every interval overlapping `query`, sorted and deduplicated.
+    pub fn overlapping(&self, query: &Interval<T>) -> Vec<usize> {
+        let mut res = Vec::new();
+
+        if !query.is_empty() {
+            self.collect_overlapping(query, 0, self.entries.len(), &mut res);
+        }
+
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+
+    fn collect_overlapping(&self, query: &Interval<T>, lo: usize, hi: usize, res: &mut Vec<usize>) {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = (lo + hi) / 2;
+        if self.max_to[mid] < query.from {
+            return;
+        }
+
+        self.collect_overlapping(query, lo, mid, res);
+
+        let (interval, tag) = &self.entries[mid];
+        if interval.from > query.to {
+            return;
+        }
+        if interval.overlap(query) {
+            res.push(*tag);
+        }
+
+        self.collect_overlapping(query, mid + 1, hi, res);
+    }
+
+    /// Maximal ranges of constant, non-zero coverage depth, in ascending order.
+    pub fn depth_ranges(&self) -> Vec<(Interval<T>, usize)> {
+        let mut starts: Vec<T> = self.entries.iter().map(|(i, _)| i.from).collect();
+        let mut ends: Vec<T> = self.entries.iter().map(|(i, _)| i.to).collect();
+        starts.sort_unstable();
+        ends.sort_unstable();
+
+        let mut res: Vec<(Interval<T>, usize)> = Vec::new();
+        let mut push = |segment: Interval<T>, depth: usize| match res.last_mut() {
+            Some((last, last_depth)) if *last_depth == depth && last.is_adjacent(&segment) => {
+                last.to = segment.to
+            }
+            _ => res.push((segment, depth)),
+        };
+
+        let (mut i, mut j, mut depth) = (0, 0, 0);
+        let mut current: Option<T> = None;
+
+        while j < ends.len() {
+            if i < starts.len() && starts[i] <= ends[j] {
+                let point = starts[i];
+                if let (Some(from), Some(to)) = (current, point.predecessor()) {
+                    if depth > 0 && from <= to {
+                        push(Interval::new(from, to), depth);
+                    }
+                }
+
+                while i < starts.len() && starts[i] == point {
+                    depth += 1;
+                    i += 1;
+                }
+                current = Some(point);
+            } else {
+                let point = ends[j];
+                if let Some(from) = current {
+                    push(Interval::new(from, point), depth);
+                }
+
+                while j < ends.len() && ends[j] == point {
+                    depth -= 1;
+                    j += 1;
+                }
+                current = point.successor();
+            }
+        }
+
+        res
+    }
+
+    pub fn coverage(&self) -> IntervalSet<T> {
+        self.entries.iter().map(|(i, _)| *i).collect()
+    }
+
+    /// Sections covered by strictly more than `k` intervals.
+    pub fn covered_more_than(&self, k: usize) -> IntervalSet<T> {
+        self.depth_ranges()
+            .into_iter()
+            .filter(|(_, depth)| *depth > k)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Smallest interval containing every entry.
+    pub fn span(&self) -> Option<Interval<T>> {
+        let from = self.entries.first()?.0.from;
+        let to = self.max_to[self.entries.len() / 2];
+
+        Some(Interval::new(from, to))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        coverage::{CoverageIndex, IntervalSet},
+        interval::Interval,
+    };
+
+    #[test]
+    fn interval_set_merges() {
+        let mut set: IntervalSet<u32> = [Interval::new(1, 3), Interval::new(8, 9)]
+            .into_iter()
+            .collect();
+
+        set.insert(Interval::new(4, 5));
+        assert_eq!(set.ranges(), &[Interval::new(1, 5), Interval::new(8, 9)]);
+
+        set.insert(Interval::new(6, 7));
+        assert_eq!(set.ranges(), &[Interval::new(1, 9)]);
+        assert_eq!(set.len(), 9);
+        assert!(!set.contains_point(10));
+    }
+
+    #[test]
+    fn gaps_within_bounds() {
+        let set: IntervalSet<u32> = [Interval::new(3, 4), Interval::new(7, 7)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            set.gaps_within(Interval::new(1, 9)),
+            vec![
+                Interval::new(1, 2),
+                Interval::new(5, 6),
+                Interval::new(8, 9)
+            ]
+        );
+        assert_eq!(set.gaps_within(Interval::new(3, 4)), vec![]);
+    }
+
+    #[test]
+    fn overlap_queries() {
+        let index = CoverageIndex::new([
+            (Interval::new(2u32, 4), 0),
+            (Interval::new(6, 8), 1),
+            (Interval::new(1, 9), 2),
+            (Interval::new(10, 12), 3),
+        ]);
+
+        assert_eq!(index.overlapping(&Interval::new(4, 6)), vec![0, 1, 2]);
+        assert_eq!(index.overlapping(&Interval::new(10, 10)), vec![3]);
+        assert_eq!(index.overlapping(&Interval::new(13, 20)), vec![]);
+        assert_eq!(index.span(), Some(Interval::new(1, 12)));
+    }
+
+    #[test]
+    fn depth_sweep() {
+        let index = CoverageIndex::new([
+            (Interval::new(1u32, 5), 0),
+            (Interval::new(3, 7), 1),
+            (Interval::new(5, 5), 2),
+            (Interval::new(9, 9), 3),
+        ]);
+
+        assert_eq!(
+            index.depth_ranges(),
+            vec![
+                (Interval::new(1, 2), 1),
+                (Interval::new(3, 4), 2),
+                (Interval::new(5, 5), 3),
+                (Interval::new(6, 7), 1),
+                (Interval::new(9, 9), 1),
+            ]
+        );
+        assert_eq!(index.covered_more_than(1).ranges(), &[Interval::new(3, 5)]);
+    }
+}
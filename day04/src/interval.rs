@@ -0,0 +1,230 @@
+use std::{cmp, fmt, str::FromStr};
+
+/// Discrete bound type, needed for everything that depends on "the next section".
+pub trait Discrete: Ord + Copy {
+    fn successor(self) -> Option<Self>;
+    fn predecessor(self) -> Option<Self>;
+    /// Number of values in `from..=to`, assuming `from <= to`.
+    fn count_between(from: Self, to: Self) -> u128;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {
+        $(
+            impl Discrete for $t {
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn predecessor(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn count_between(from: Self, to: Self) -> u128 {
+                    (to as i128 - from as i128) as u128 + 1
+                }
+            }
+        )*
+    };
+}
+
+impl_discrete!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Inclusive interval `from..=to`. An interval with `from > to` is empty.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Interval<T> {
+    pub from: T,
+    pub to: T,
+}
+
+impl<T: Ord + Copy> Interval<T> {
+    pub fn new(from: T, to: T) -> Self {
+        Interval { from, to }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.from > self.to
+    }
+
+    pub fn contains_point(&self, point: T) -> bool {
+        self.from <= point && point <= self.to
+    }
+
+    pub fn contains(&self, other: &Self) -> bool {
+        other.is_empty() || (self.from <= other.from && self.to >= other.to)
+    }
+
+    pub fn overlap(&self, other: &Self) -> bool {
+        !self.is_empty() && !other.is_empty() && self.from <= other.to && other.from <= self.to
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let res = Interval::new(cmp::max(self.from, other.from), cmp::min(self.to, other.to));
+
+        if res.is_empty() {
+            None
+        } else {
+            Some(res)
+        }
+    }
+}
+
+impl<T: Discrete> Interval<T> {
+    pub fn len(&self) -> u128 {
+        if self.is_empty() {
+            0
+        } else {
+            T::count_between(self.from, self.to)
+        }
+    }
+
+    /// True when the two intervals don't overlap but leave no gap between them.
+    pub fn is_adjacent(&self, other: &Self) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return false;
+        }
+
+        self.to.successor() == Some(other.from) || other.to.successor() == Some(self.from)
+    }
+
+    /// Union of the two intervals, if it is itself an interval.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        if other.is_empty() {
+            return Some(*self);
+        }
+        if self.is_empty() {
+            return Some(*other);
+        }
+
+        if self.overlap(other) || self.is_adjacent(other) {
+            Some(Interval::new(
+                cmp::min(self.from, other.from),
+                cmp::max(self.to, other.to),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Sections of `self` not covered by `other`, in ascending order.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let Some(common) = self.intersection(other) else {
+            return vec![*self];
+        };
+
+        let mut res = Vec::new();
+
+        if let Some(before) = common.from.predecessor() {
+            if self.from <= before {
+                res.push(Interval::new(self.from, before));
+            }
+        }
+
+        if let Some(after) = common.to.successor() {
+            if after <= self.to {
+                res.push(Interval::new(after, self.to));
+            }
+        }
+
+        res
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.from, self.to)
+    }
+}
+
+impl<T: FromStr> FromStr for Interval<T> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Skip the first char so that a leading minus sign isn't taken as the separator.
+        let Some(sep) = s
+            .get(1..)
+            .and_then(|rest| rest.find('-'))
+            .map(|idx| idx + 1)
+        else {
+            return Err(format!("Missing '-' in interval '{}'", s));
+        };
+
+        let (from, to) = (&s[..sep], &s[sep + 1..]);
+
+        Ok(Interval {
+            from: from
+                .parse()
+                .map_err(|_| format!("Invalid interval start '{}'", from))?,
+            to: to
+                .parse()
+                .map_err(|_| format!("Invalid interval end '{}'", to))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::interval::Interval;
+
+    #[test]
+    fn intersection_and_union() {
+        let a = Interval::new(2, 6);
+        let b = Interval::new(4, 8);
+
+        assert_eq!(a.intersection(&b), Some(Interval::new(4, 6)));
+        assert_eq!(a.union(&b), Some(Interval::new(2, 8)));
+        assert_eq!(Interval::new(2, 3).intersection(&Interval::new(5, 6)), None);
+    }
+
+    #[test]
+    fn adjacent_intervals_merge() {
+        let a = Interval::new(2, 4);
+        let b = Interval::new(5, 7);
+
+        assert!(a.is_adjacent(&b));
+        assert!(!a.overlap(&b));
+        assert_eq!(a.union(&b), Some(Interval::new(2, 7)));
+        assert_eq!(a.union(&Interval::new(6, 7)), None);
+    }
+
+    #[test]
+    fn difference_splits() {
+        let a = Interval::new(1u32, 10);
+
+        assert_eq!(
+            a.difference(&Interval::new(3, 5)),
+            vec![Interval::new(1, 2), Interval::new(6, 10)]
+        );
+        assert_eq!(
+            a.difference(&Interval::new(0, 4)),
+            vec![Interval::new(5, 10)]
+        );
+        assert_eq!(a.difference(&Interval::new(0, 20)), vec![]);
+        assert_eq!(a.difference(&Interval::new(20, 30)), vec![a]);
+    }
+
+    #[test]
+    fn len_and_emptiness() {
+        assert_eq!(Interval::new(3u32, 7).len(), 5);
+        assert_eq!(Interval::new(7u32, 3).len(), 0);
+        assert!(Interval::new(7u32, 3).is_empty());
+        assert!(Interval::new(1u32, 2).contains(&Interval::new(9, 3)));
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let a: Interval<u32> = "2-8".parse().unwrap();
+        assert_eq!(a, Interval::new(2, 8));
+        assert_eq!(a.to_string(), "2-8");
+
+        let b: Interval<i32> = "-3--1".parse().unwrap();
+        assert_eq!(b, Interval::new(-3, -1));
+
+        assert!("2_8".parse::<Interval<u32>>().is_err());
+        assert!("a-8".parse::<Interval<u32>>().is_err());
+    }
+}
@@ -1,95 +1,84 @@
-use once_cell::sync::Lazy;
-use regex::Regex;
+use day04::{
+    assignment::{AssignmentRow, Camp},
+    report::{render_row, Report},
+};
 use std::{
     fs::File,
     io::{BufRead, BufReader},
 };
 
-static REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?P<first_from>\d+)-(?P<first_to>\d+),(?P<second_from>\d+)-(?P<second_to>\d+)")
-        .unwrap()
-});
+fn main() -> Result<(), std::io::Error> {
+    let rows = read_rows()?;
 
-#[derive(Debug, Eq, PartialEq)]
-struct Assignment {
-    from: u32,
-    to: u32,
-}
+    let num_complete_overlap = rows.iter().filter(|ass| ass.complete_overlap()).count();
 
-impl Assignment {
-    fn contains(&self, other: &Self) -> bool {
-        self.from <= other.from && self.to >= other.to
-    }
+    println!("Complete overlap: {}", num_complete_overlap);
 
-    fn overlap(&self, other: &Self) -> bool {
-        (self.from >= other.from && self.from <= other.to)
-            || (self.to >= other.from && self.to <= other.to)
-            || (other.from >= self.from && other.from <= self.to)
-            || (other.to >= self.from && other.to <= self.to)
-    }
-}
+    let num_overlap = rows.iter().filter(|ass| ass.overlap()).count();
 
-struct AssignmentRow {
-    first_elf_assignment: Assignment,
-    second_elf_assignment: Assignment,
-}
+    println!("Overlaps: {}", num_overlap);
 
-impl AssignmentRow {
-    fn complete_overlap(&self) -> bool {
-        self.first_elf_assignment
-            .contains(&self.second_elf_assignment)
-            || self
-                .second_elf_assignment
-                .contains(&self.first_elf_assignment)
-    }
+    let report: Report = rows.iter().collect();
+    print!("{}", report);
 
-    fn overlap(&self) -> bool {
-        self.first_elf_assignment
-            .overlap(&self.second_elf_assignment)
+    if std::env::args().any(|arg| arg == "--map") {
+        for row in rows.iter().filter(|ass| ass.overlap()) {
+            match render_row(row) {
+                Some(map) => println!("{}", map),
+                None => println!("Row too wide to map\n"),
+            }
+        }
     }
-}
 
-impl TryFrom<String> for AssignmentRow {
-    fn try_from(value: String) -> Result<Self, String> {
-        let Some(caps) = REGEX.captures(&value) else{
-            return Err("Error while parsing".into());
-        };
-
-        Ok(AssignmentRow {
-            first_elf_assignment: Assignment {
-                from: caps["first_from"].parse().unwrap(),
-                to: caps["first_to"].parse().unwrap(),
-            },
-            second_elf_assignment: Assignment {
-                from: caps["second_from"].parse().unwrap(),
-                to: caps["second_to"].parse().unwrap(),
-            },
-        })
+    let camp = Camp::new(rows);
+
+    println!("Uncovered sections: {:?}", camp.uncovered());
+
+    if let Some(k) = more_than()? {
+        println!(
+            "Sections covered by more than {} elves: {}",
+            k,
+            camp.covered_by_more_than(k).len()
+        );
     }
 
-    type Error = String;
+    Ok(())
 }
 
-fn main() -> Result<(), std::io::Error> {
-    let num_complete_overlap = get_input_file_handler()?
-        .lines()
-        .filter_map(|line| line.ok())
-        .filter_map(|line| AssignmentRow::try_from(line).ok())
-        .filter(|ass| ass.complete_overlap())
-        .count();
+/// Value of the `--more-than <k>` argument.
+fn more_than() -> Result<Option<usize>, std::io::Error> {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(idx) = args.iter().position(|arg| arg == "--more-than") else {
+        return Ok(None);
+    };
+
+    args.get(idx + 1)
+        .and_then(|k| k.parse().ok())
+        .map(Some)
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "--more-than needs a number of elves",
+            )
+        })
+}
 
-    println!("Complete overlap: {}", num_complete_overlap);
+fn read_rows() -> Result<Vec<AssignmentRow>, std::io::Error> {
+    let mut rows = Vec::new();
 
-    let num_overlap = get_input_file_handler()?
-        .lines()
-        .filter_map(|line| line.ok())
-        .filter_map(|line| AssignmentRow::try_from(line).ok())
-        .filter(|ass| ass.overlap())
-        .count();
+    for (idx, line) in get_input_file_handler()?.lines().enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
 
-    println!("Overlaps: {}", num_overlap);
+        match AssignmentRow::try_from(line) {
+            Ok(row) => rows.push(row),
+            Err(err) => eprintln!("Skipping line {}: {}", idx + 1, err),
+        }
+    }
 
-    Ok(())
+    Ok(rows)
 }
 
 fn get_input_file_handler() -> Result<BufReader<File>, std::io::Error> {
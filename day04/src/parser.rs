@@ -0,0 +1,163 @@
+use std::fmt;
+
+use crate::assignment::Assignment;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ParseErrorKind {
+    ExpectedDigit,
+    ExpectedDash,
+    /// Something other than `,` or the end of the line after a range.
+    TrailingInput,
+    Overflow,
+    ReversedRange {
+        from: u32,
+        to: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ParseError {
+    /// Byte offset in the line where the problem was found.
+    pub position: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::ExpectedDigit => write!(f, "expected a digit")?,
+            ParseErrorKind::ExpectedDash => write!(f, "expected '-'")?,
+            ParseErrorKind::TrailingInput => write!(f, "expected ',' or end of line")?,
+            ParseErrorKind::Overflow => write!(f, "section number too large")?,
+            ParseErrorKind::ReversedRange { from, to } => {
+                write!(f, "range {}-{} ends before it starts", from, to)?
+            }
+        }
+
+        write!(f, " at byte {}", self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Iterator over the `a-b` ranges of a comma separated line.
+///
+/// Parsing stops at the first error, which is yielded as the last item.
+pub struct AssignmentParser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    done: bool,
+}
+
+impl<'a> AssignmentParser<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        AssignmentParser {
+            input,
+            pos: 0,
+            done: false,
+        }
+    }
+
+    fn error(&self, position: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError { position, kind }
+    }
+
+    fn number(&mut self) -> Result<u32, ParseError> {
+        let start = self.pos;
+        let mut res: u32 = 0;
+
+        while let Some(&byte) = self.input.get(self.pos) {
+            if !byte.is_ascii_digit() {
+                break;
+            }
+
+            res = res
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(u32::from(byte - b'0')))
+                .ok_or(self.error(start, ParseErrorKind::Overflow))?;
+            self.pos += 1;
+        }
+
+        if self.pos == start {
+            return Err(self.error(start, ParseErrorKind::ExpectedDigit));
+        }
+
+        Ok(res)
+    }
+
+    fn range(&mut self) -> Result<Assignment, ParseError> {
+        let start = self.pos;
+        let from = self.number()?;
+
+        if self.input.get(self.pos) != Some(&b'-') {
+            return Err(self.error(self.pos, ParseErrorKind::ExpectedDash));
+        }
+        self.pos += 1;
+
+        let to = self.number()?;
+        if from > to {
+            return Err(self.error(start, ParseErrorKind::ReversedRange { from, to }));
+        }
+
+        match self.input.get(self.pos) {
+            None => self.done = true,
+            Some(b',') => self.pos += 1,
+            Some(_) => return Err(self.error(self.pos, ParseErrorKind::TrailingInput)),
+        }
+
+        Ok(Assignment::new(from, to))
+    }
+}
+
+impl Iterator for AssignmentParser<'_> {
+    type Item = Result<Assignment, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let res = self.range();
+        if res.is_err() {
+            self.done = true;
+        }
+
+        Some(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        assignment::Assignment,
+        parser::{AssignmentParser, ParseError, ParseErrorKind},
+    };
+
+    fn parse(line: &str) -> Result<Vec<Assignment>, ParseError> {
+        AssignmentParser::new(line.as_bytes()).collect()
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(
+            parse("2-4,6-8"),
+            Ok(vec![Assignment::new(2, 4), Assignment::new(6, 8)])
+        );
+        assert_eq!(parse("12-12"), Ok(vec![Assignment::new(12, 12)]));
+    }
+
+    #[test]
+    fn reports_positions() {
+        let err = |position, kind| Err(ParseError { position, kind });
+
+        assert_eq!(parse(""), err(0, ParseErrorKind::ExpectedDigit));
+        assert_eq!(parse("2-4,6-8 x"), err(7, ParseErrorKind::TrailingInput));
+        assert_eq!(parse("2-4,"), err(4, ParseErrorKind::ExpectedDigit));
+        assert_eq!(parse("2-4,68"), err(6, ParseErrorKind::ExpectedDash));
+        assert_eq!(
+            parse("2-4,8-6"),
+            err(4, ParseErrorKind::ReversedRange { from: 8, to: 6 })
+        );
+        assert_eq!(parse("1-99999999999"), err(2, ParseErrorKind::Overflow));
+    }
+}
@@ -0,0 +1,185 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    assignment::{Assignment, AssignmentRow},
+    interval::{Discrete, Interval},
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Overlap {
+    Disjoint,
+    Touching,
+    Partial,
+    FirstContainsSecond,
+    SecondContainsFirst,
+    Identical,
+}
+
+impl Overlap {
+    pub const ALL: [Overlap; 6] = [
+        Overlap::Disjoint,
+        Overlap::Touching,
+        Overlap::Partial,
+        Overlap::FirstContainsSecond,
+        Overlap::SecondContainsFirst,
+        Overlap::Identical,
+    ];
+
+    pub fn classify<T: Discrete>(first: &Interval<T>, second: &Interval<T>) -> Self {
+        if first == second {
+            Overlap::Identical
+        } else if first.contains(second) {
+            Overlap::FirstContainsSecond
+        } else if second.contains(first) {
+            Overlap::SecondContainsFirst
+        } else if first.overlap(second) {
+            Overlap::Partial
+        } else if first.is_adjacent(second) {
+            Overlap::Touching
+        } else {
+            Overlap::Disjoint
+        }
+    }
+}
+
+impl fmt::Display for Overlap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Overlap::Disjoint => "disjoint",
+            Overlap::Touching => "touching",
+            Overlap::Partial => "partial overlap",
+            Overlap::FirstContainsSecond => "first contains second",
+            Overlap::SecondContainsFirst => "second contains first",
+            Overlap::Identical => "identical",
+        };
+
+        f.pad(name)
+    }
+}
+
+/// Class of every pair of assignments in the row.
+pub fn classify_row(row: &AssignmentRow) -> Vec<((usize, usize), Overlap)> {
+    row.pairs()
+        .map(|(i, j)| {
+            (
+                (i, j),
+                Overlap::classify(&row.assignments[i], &row.assignments[j]),
+            )
+        })
+        .collect()
+}
+
+/// Number of assignment pairs per overlap class.
+#[derive(Debug, Default)]
+pub struct Report {
+    counts: BTreeMap<Overlap, usize>,
+}
+
+impl Report {
+    pub fn add_row(&mut self, row: &AssignmentRow) {
+        for (_, class) in classify_row(row) {
+            *self.counts.entry(class).or_default() += 1;
+        }
+    }
+
+    pub fn count(&self, class: Overlap) -> usize {
+        self.counts.get(&class).copied().unwrap_or(0)
+    }
+}
+
+impl<'a> FromIterator<&'a AssignmentRow> for Report {
+    fn from_iter<I: IntoIterator<Item = &'a AssignmentRow>>(iter: I) -> Self {
+        let mut report = Report::default();
+        for row in iter {
+            report.add_row(row);
+        }
+        report
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for class in Overlap::ALL {
+            writeln!(f, "{:>22}: {}", class, self.count(class))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Widest row `render_row` draws.
+pub const MAX_MAP_WIDTH: u128 = 200;
+
+/// Map of the sections in `bounds` where the assigned ones show their last
+/// digit, e.g. `.234.....` for `2-4` over `1-9`.
+pub fn section_map(assignment: &Assignment, bounds: &Assignment) -> String {
+    (bounds.from..=bounds.to)
+        .map(|section| {
+            if assignment.contains_point(section) {
+                char::from_digit(section % 10, 10).unwrap()
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+/// One map line per assignment, from the lowest to the highest section of
+/// the row. `None` if that is more than `MAX_MAP_WIDTH` sections.
+pub fn render_row(row: &AssignmentRow) -> Option<String> {
+    let from = row.assignments.iter().map(|a| a.from).min()?;
+    let to = row.assignments.iter().map(|a| a.to).max()?;
+    let bounds = Assignment::new(from, to);
+    if bounds.len() > MAX_MAP_WIDTH {
+        return None;
+    }
+
+    Some(
+        row.assignments
+            .iter()
+            .map(|a| format!("{}  {}\n", section_map(a, &bounds), a))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        assignment::{Assignment, AssignmentRow},
+        report::{render_row, Overlap, Report},
+    };
+
+    #[test]
+    fn classification() {
+        let class = |a, b, c, d| Overlap::classify(&Assignment::new(a, b), &Assignment::new(c, d));
+
+        assert_eq!(class(2, 4, 6, 8), Overlap::Disjoint);
+        assert_eq!(class(2, 4, 5, 8), Overlap::Touching);
+        assert_eq!(class(5, 7, 7, 9), Overlap::Partial);
+        assert_eq!(class(2, 8, 3, 7), Overlap::FirstContainsSecond);
+        assert_eq!(class(6, 6, 4, 6), Overlap::SecondContainsFirst);
+        assert_eq!(class(3, 5, 3, 5), Overlap::Identical);
+    }
+
+    #[test]
+    fn report_and_map() {
+        let rows: Vec<AssignmentRow> = ["2-4,6-8", "2-8,3-7", "5-7,7-9"]
+            .iter()
+            .map(|line| line.parse().unwrap())
+            .collect();
+
+        let report: Report = rows.iter().collect();
+        assert_eq!(report.count(Overlap::Disjoint), 1);
+        assert_eq!(report.count(Overlap::FirstContainsSecond), 1);
+        assert_eq!(report.count(Overlap::Identical), 0);
+
+        assert_eq!(
+            render_row(&rows[0]).unwrap(),
+            "234....  2-4\n....678  6-8\n"
+        );
+        assert_eq!(render_row(&rows[2]).unwrap(), "567..  5-7\n..789  7-9\n");
+
+        let wide: AssignmentRow = "1-4000000000,2-3".parse().unwrap();
+        assert_eq!(render_row(&wide), None);
+    }
+}
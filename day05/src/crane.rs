@@ -0,0 +1,185 @@
+use std::num::NonZeroUsize;
+
+use crate::{
+    moves::{Move, MoveError, Strictness},
+    stack::CargoStack,
+};
+
+pub trait Crane {
+    /// Executes a single move that has already been checked against the stack.
+    fn apply<T>(&self, stack: &mut CargoStack<T>, mv: &Move);
+
+    /// Most crates moved together in one lift, `None` when unlimited.
+    fn lift_capacity(&self) -> Option<usize>;
+
+    fn run<'a, T>(
+        &self,
+        stack: &mut CargoStack<T>,
+        moves: impl IntoIterator<Item = &'a Move>,
+        strictness: Strictness,
+    ) -> Result<(), MoveError> {
+        self.run_with(stack, moves, strictness, |_, _, _| {})
+    }
+
+    /// Like `run`, calling `on_step` with the move number, the executed move
+    /// and the resulting stacks after every move.
+    fn run_with<'a, T>(
+        &self,
+        stack: &mut CargoStack<T>,
+        moves: impl IntoIterator<Item = &'a Move>,
+        strictness: Strictness,
+        mut on_step: impl FnMut(usize, &Move, &CargoStack<T>),
+    ) -> Result<(), MoveError> {
+        for (idx, mv) in moves.into_iter().enumerate() {
+            let mv = mv.check(stack, strictness).map_err(|kind| MoveError {
+                number: idx + 1,
+                kind,
+            })?;
+
+            self.apply(stack, &mv);
+            on_step(idx + 1, &mv, stack);
+        }
+
+        Ok(())
+    }
+}
+
+/// Picks up one crate at a time.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn apply<T>(&self, stack: &mut CargoStack<T>, mv: &Move) {
+        stack.transfer(mv.from - 1, mv.to - 1, mv.quantity, true);
+    }
+
+    fn lift_capacity(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// Picks up all the crates of a move at once.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn apply<T>(&self, stack: &mut CargoStack<T>, mv: &Move) {
+        stack.transfer(mv.from - 1, mv.to - 1, mv.quantity, false);
+    }
+
+    fn lift_capacity(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Picks up at most `capacity` crates per lift.
+pub struct LimitedCrane {
+    capacity: NonZeroUsize,
+}
+
+impl LimitedCrane {
+    /// `None` for a crane that can't lift anything.
+    pub fn new(capacity: usize) -> Option<Self> {
+        NonZeroUsize::new(capacity).map(|capacity| LimitedCrane { capacity })
+    }
+}
+
+impl Crane for LimitedCrane {
+    fn apply<T>(&self, stack: &mut CargoStack<T>, mv: &Move) {
+        let mut remaining = mv.quantity;
+
+        while remaining > 0 {
+            let quantity = remaining.min(self.capacity.get());
+            stack.transfer(mv.from - 1, mv.to - 1, quantity, false);
+            remaining -= quantity;
+        }
+    }
+
+    fn lift_capacity(&self) -> Option<usize> {
+        Some(self.capacity.get())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        crane::{Crane, CrateMover9000, CrateMover9001, LimitedCrane},
+        moves::{Move, MoveError, MoveErrorKind, Strictness},
+        stack::CargoStack,
+    };
+
+    fn tops(crane: &impl Crane) -> Vec<String> {
+        let mut stack = CargoStack::try_from("[A]\n[B]\n[C]\n[D]\n 1   2 ".to_string()).unwrap();
+        let mv = Move {
+            quantity: 4,
+            from: 1,
+            to: 2,
+        };
+
+        crane.run(&mut stack, [&mv], Strictness::Strict).unwrap();
+        stack.stacks[1].clone()
+    }
+
+    #[test]
+    fn crane_models() {
+        assert_eq!(tops(&CrateMover9000), ["A", "B", "C", "D"]);
+        assert_eq!(tops(&CrateMover9001), ["D", "C", "B", "A"]);
+        let limited = |capacity| tops(&LimitedCrane::new(capacity).unwrap());
+
+        assert_eq!(limited(1), tops(&CrateMover9000));
+        assert_eq!(limited(4), tops(&CrateMover9001));
+        assert_eq!(limited(3), ["C", "B", "A", "D"]);
+        assert!(LimitedCrane::new(0).is_none());
+    }
+
+    #[test]
+    fn invalid_moves() {
+        let mut stack = CargoStack::try_from("[A]\n 1   2 ".to_string()).unwrap();
+        let moves = [
+            Move {
+                quantity: 1,
+                from: 1,
+                to: 2,
+            },
+            Move {
+                quantity: 2,
+                from: 2,
+                to: 1,
+            },
+            Move {
+                quantity: 1,
+                from: 0,
+                to: 1,
+            },
+        ];
+
+        let err = CrateMover9000
+            .run(&mut stack, &moves[..2], Strictness::Strict)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            MoveError {
+                number: 2,
+                kind: MoveErrorKind::NotEnoughCrates {
+                    requested: 2,
+                    available: 1
+                }
+            }
+        );
+
+        CrateMover9000
+            .run(&mut stack, &moves[1..2], Strictness::Lenient)
+            .unwrap();
+        assert_eq!(stack.stacks[0], ["A"]);
+
+        let err = CrateMover9001
+            .run(&mut stack, &moves, Strictness::Lenient)
+            .unwrap_err();
+        assert_eq!(err.number, 3);
+        assert_eq!(
+            err.kind,
+            MoveErrorKind::InvalidStack {
+                stack: 0,
+                stacks: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{
+    crane::Crane,
+    moves::{Move, MoveError, Strictness},
+    stack::CargoStack,
+};
+
+/// An executed move, with the crates it took and the way it left them, so it
+/// can be undone and redone whatever crane executed it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Step {
+    pub mv: Move,
+    taken: Vec<String>,
+    placed: Vec<String>,
+}
+
+/// Stepwise simulation of a procedure that can move backward and forward.
+pub struct Simulation<C> {
+    crane: C,
+    stack: CargoStack,
+    strictness: Strictness,
+    steps: Vec<Step>,
+    /// Number of steps currently applied to `stack`.
+    position: usize,
+}
+
+impl<C: Crane> Simulation<C> {
+    pub fn new(crane: C, stack: CargoStack, strictness: Strictness) -> Self {
+        Simulation {
+            crane,
+            stack,
+            strictness,
+            steps: Vec::new(),
+            position: 0,
+        }
+    }
+
+    pub fn stack(&self) -> &CargoStack {
+        &self.stack
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Executes a new move. Steps that were undone are discarded.
+    pub fn apply(&mut self, mv: &Move) -> Result<(), MoveError> {
+        let mv = mv
+            .check(&self.stack, self.strictness)
+            .map_err(|kind| MoveError {
+                number: self.position + 1,
+                kind,
+            })?;
+
+        let from = &self.stack.stacks[mv.from - 1];
+        let taken = from[from.len() - mv.quantity..].to_vec();
+
+        self.crane.apply(&mut self.stack, &mv);
+
+        let to = &self.stack.stacks[mv.to - 1];
+        let placed = to[to.len() - mv.quantity..].to_vec();
+
+        self.steps.truncate(self.position);
+        self.steps.push(Step { mv, taken, placed });
+        self.position += 1;
+
+        Ok(())
+    }
+
+    pub fn run<'a>(&mut self, moves: impl IntoIterator<Item = &'a Move>) -> Result<(), MoveError> {
+        for mv in moves {
+            self.apply(mv)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reverts the last applied step. Returns false at the beginning.
+    pub fn undo(&mut self) -> bool {
+        let Some(step) = self.position.checked_sub(1).map(|idx| &self.steps[idx]) else {
+            return false;
+        };
+
+        let to = &mut self.stack.stacks[step.mv.to - 1];
+        to.truncate(to.len() - step.placed.len());
+        self.stack.stacks[step.mv.from - 1].extend_from_slice(&step.taken);
+
+        self.position -= 1;
+        true
+    }
+
+    /// Applies again the next undone step. Returns false at the end.
+    pub fn redo(&mut self) -> bool {
+        let Some(step) = self.steps.get(self.position) else {
+            return false;
+        };
+
+        let from = &mut self.stack.stacks[step.mv.from - 1];
+        from.truncate(from.len() - step.taken.len());
+        self.stack.stacks[step.mv.to - 1].extend_from_slice(&step.placed);
+
+        self.position += 1;
+        true
+    }
+
+    /// Moves to the arrangement after `position` steps, clamped to the
+    /// recorded ones.
+    pub fn seek(&mut self, position: usize) {
+        while self.position > position && self.undo() {}
+        while self.position < position && self.redo() {}
+    }
+
+    /// The arrangement after `position` steps.
+    pub fn snapshot(&mut self, position: usize) -> CargoStack {
+        self.seek(position);
+        self.stack.clone()
+    }
+
+    /// First position whose arrangement satisfies `condition`, assuming that
+    /// once it holds it keeps holding for the rest of the procedure. Leaves
+    /// the simulation at that position, or at the end if there's none.
+    pub fn bisect(&mut self, mut condition: impl FnMut(&CargoStack) -> bool) -> Option<usize> {
+        let (mut lo, mut hi) = (0, self.steps.len() + 1);
+
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            self.seek(mid);
+
+            if condition(&self.stack) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+
+        self.seek(lo);
+        (lo <= self.steps.len()).then_some(lo)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        crane::{CrateMover9000, CrateMover9001, LimitedCrane},
+        history::Simulation,
+        input::{test::EXAMPLE, PuzzleInput},
+        moves::Strictness,
+    };
+
+    #[test]
+    fn undo_and_redo() {
+        let input: PuzzleInput = EXAMPLE.parse().unwrap();
+        let mut simulation = Simulation::new(
+            LimitedCrane::new(2).unwrap(),
+            input.stack.clone(),
+            Strictness::Strict,
+        );
+
+        simulation.run(&input.moves).unwrap();
+        let end = simulation.stack().clone();
+
+        while simulation.undo() {}
+        assert_eq!(simulation.position(), 0);
+        assert_eq!(simulation.stack(), &input.stack);
+
+        simulation.seek(4);
+        assert_eq!(simulation.stack(), &end);
+        assert!(!simulation.redo());
+    }
+
+    #[test]
+    fn snapshots_match_replays() {
+        let input: PuzzleInput = EXAMPLE.parse().unwrap();
+        let mut simulation =
+            Simulation::new(CrateMover9001, input.stack.clone(), Strictness::Strict);
+        simulation.run(&input.moves).unwrap();
+
+        for position in 0..=input.moves.len() {
+            let mut replay =
+                Simulation::new(CrateMover9001, input.stack.clone(), Strictness::Strict);
+            replay.run(&input.moves[..position]).unwrap();
+
+            assert_eq!(simulation.snapshot(position), *replay.stack());
+        }
+    }
+
+    #[test]
+    fn bisect_first_misplaced_crate() {
+        let input: PuzzleInput = EXAMPLE.parse().unwrap();
+        let mut simulation =
+            Simulation::new(CrateMover9000, input.stack.clone(), Strictness::Strict);
+        simulation.run(&input.moves).unwrap();
+
+        // Z leaves stack 1 with the second move and never comes back.
+        let found = simulation.bisect(|stack| !stack.stacks[0].iter().any(|c| c == "Z"));
+        assert_eq!(found, Some(2));
+        assert_eq!(simulation.position(), 2);
+
+        assert_eq!(simulation.bisect(|stack| stack.stacks.is_empty()), None);
+    }
+}
@@ -0,0 +1,127 @@
+use std::str::FromStr;
+
+use crate::{
+    crane::Crane,
+    moves::{Move, MoveError, Strictness},
+    stack::CargoStack,
+};
+
+/// The puzzle input as published: the stack drawing, a blank line, then the
+/// rearrangement procedure.
+#[derive(Debug)]
+pub struct PuzzleInput {
+    pub stack: CargoStack,
+    pub moves: Vec<Move>,
+}
+
+impl PuzzleInput {
+    /// Runs the procedure on a copy of the starting stacks, returning the
+    /// final arrangement.
+    pub fn solve(
+        &self,
+        crane: &impl Crane,
+        strictness: Strictness,
+    ) -> Result<CargoStack, MoveError> {
+        let mut stack = self.stack.clone();
+        crane.run(&mut stack, &self.moves, strictness)?;
+
+        Ok(stack)
+    }
+}
+
+impl std::fmt::Display for PuzzleInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.stack.write_drawing(f)?;
+        writeln!(f, "\n")?;
+
+        for mv in &self.moves {
+            writeln!(f, "{}", mv)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for PuzzleInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.lines().collect();
+
+        let Some(separator) = lines.iter().position(|ln| ln.trim().is_empty()) else {
+            return Err("Cannot find the blank line after the stack drawing".into());
+        };
+
+        let stack =
+            CargoStack::try_from(lines[..separator].join("\n")).map_err(|err| err.to_string())?;
+
+        let mut moves = Vec::new();
+        for (idx, ln) in lines.iter().enumerate().skip(separator + 1) {
+            if ln.trim().is_empty() {
+                continue;
+            }
+
+            let mv = Move::try_from(ln.to_string())
+                .map_err(|err| format!("Line {}: {}", idx + 1, err))?;
+            moves.push(mv);
+        }
+
+        Ok(PuzzleInput { stack, moves })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use crate::{
+        crane::{CrateMover9000, CrateMover9001},
+        input::PuzzleInput,
+        moves::Strictness,
+    };
+
+    /// The puzzle example, drawing lines padded to the full width.
+    pub(crate) const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn splits_drawing_and_moves() {
+        let input: PuzzleInput = EXAMPLE.parse().unwrap();
+
+        assert_eq!(input.stack.stacks.len(), 3);
+        assert_eq!(input.stack.stacks[1], ["M", "C", "D"]);
+        assert_eq!(input.moves.len(), 4);
+        assert_eq!(input.moves[1].quantity, 3);
+    }
+
+    #[test]
+    fn rejects_missing_separator() {
+        assert!("[A]\n 1 \nmove 1 from 1 to 1"
+            .parse::<PuzzleInput>()
+            .is_err());
+    }
+
+    #[test]
+    fn example_answers() {
+        let input: PuzzleInput = EXAMPLE.parse().unwrap();
+
+        let part_one = input.solve(&CrateMover9000, Strictness::Strict).unwrap();
+        assert_eq!(part_one.top_crates(), "CMZ");
+
+        let part_two = input.solve(&CrateMover9001, Strictness::Strict).unwrap();
+        assert_eq!(part_two.top_crates(), "MCD");
+    }
+
+    #[test]
+    fn serializes_to_input_format() {
+        let input: PuzzleInput = EXAMPLE.parse().unwrap();
+
+        assert_eq!(input.to_string(), EXAMPLE);
+    }
+}
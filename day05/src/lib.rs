@@ -0,0 +1,8 @@
+pub mod crane;
+pub mod history;
+pub mod input;
+pub mod moves;
+pub mod optimize;
+pub mod provenance;
+pub mod search;
+pub mod stack;
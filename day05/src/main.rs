@@ -1,240 +1,244 @@
-use color_eyre::Report;
+use color_eyre::{eyre::eyre, Report};
+use day05::{
+    crane::{Crane, CrateMover9000, CrateMover9001},
+    input::PuzzleInput,
+    moves::Strictness,
+    optimize::{equivalent, optimize},
+    provenance::Provenance,
+    search::{find_procedure, SearchResult},
+    stack::CargoStack,
+};
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
 use std::{
-    collections::VecDeque,
     fs::File,
-    io::{BufRead, BufReader, Read},
+    io::{BufReader, Read, Write},
+    thread,
+    time::Duration,
 };
 
-use once_cell::sync::Lazy;
-use regex::Regex;
-
-static REGEX_MOVES: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"move (?P<quantity>\d+) from (?P<from>\d+) to (?P<to>\d+)").unwrap());
+/// Default number of arrangements `--reach` explores before giving up.
+const MAX_SEARCH_STATES: usize = 1_000_000;
 
-static REGEX_STACK: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?P<token>\[(?P<name>\S)\]|\s{3}) ?").unwrap());
-
-#[derive(Debug)]
-struct Move {
-    quantity: u8,
-    from: usize,
-    to: usize,
+#[derive(Debug, Clone, Copy)]
+enum Mode {
+    Quiet,
+    /// Log every move and the stacks after it.
+    Trace,
+    /// Redraw the stacks in the terminal after every move.
+    Animate(Duration),
 }
 
-impl std::fmt::Display for Move {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{} | {} -> {}", self.quantity, self.from, self.to)
-    }
+#[derive(Debug, Clone)]
+struct Options {
+    mode: Mode,
+    /// Print the final stacks along with the answer.
+    show_stacks: bool,
+    /// Drawing of an arrangement to find a procedure for.
+    reach: Option<String>,
+    /// Arrangements explored by each search before giving up.
+    reach_limit: usize,
+    /// Run the optimized procedure instead of the original one.
+    optimize: bool,
+    /// Label of the crates whose moves should be reported.
+    track: Option<String>,
 }
 
-impl TryFrom<String> for Move {
-    type Error = String;
-
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        let Some(caps) = REGEX_MOVES.captures(&value) else {
-            return Err("Connot find anything".into());
+impl Options {
+    fn from_args() -> Result<Self, Report> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        // The argument after a flag is its value unless it's another flag.
+        let value_of = |flag: &str| {
+            args.iter()
+                .position(|arg| arg == flag)
+                .map(|idx| args.get(idx + 1).filter(|value| !value.starts_with("--")))
         };
 
-        Ok(Move {
-            quantity: caps["quantity"].parse().unwrap(),
-            from: caps["from"].parse().unwrap(),
-            to: caps["to"].parse().unwrap(),
-        })
-    }
-}
-#[derive(Debug)]
-struct CargoStack {
-    stacks: Vec<VecDeque<char>>,
-}
-// ┌────────────────────────────────────────────────────────────────────────┐
-// │                                                                        │
-// │                                                                        │
-// │        [      ┌────────┐\w  ┌────────┐ ]  ┌────────┐                   │
-// │      ┌───────►│ OPEN   ├───►│ NAME   ├───►│ CLOSE  ├──────────┐        │
-// │      │        └────────┘    └────────┘    └────┬───┘          │        │
-// │      │                                         │              │        │
-// │      │                                         ▼              ▼        │
-// │  ┌───┴────┐                               ┌────────┐     ┌────────┐    │
-// └─►│ INIT   │                               │ END LN │     │ SPACE  ├────┘
-//    └───┬────┘                               └────────┘     └────────┘
-//        │                                         ▲              ▲
-//        │                                         │              │
-//        │        ┌────────┐    ┌────────┐    ┌────┴───┐          │
-//        └───────►│ 1 SPAC ├───►│ 2 SPAC ├───►│ 3 SPAC ├──────────┘
-//          ' '    └───┬────┘' ' └────────┘' ' └────────┘
-//                     │                            ▲
-//                     │         ┌────────┐         │
-//                     └────────►│ NUMBER ├─────────┘
-//                               └────────┘
-#[derive(Debug, Copy, Clone)]
-enum ParsingStates {
-    Init,
-    Open,
-    ObjectName,
-    Close,
-    FSpace,
-    SSpace,
-    Number,
-    TSpace,
-}
-
-impl TryFrom<String> for CargoStack {
-    type Error = String;
-
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        let mut res_stack: Vec<VecDeque<char>> = Vec::new();
-        let mut couting_first_line = true;
-
-        for line in value.lines() {
-            let mut index = 0;
-            let mut current_state = ParsingStates::Init;
-
-            for next_char in line.chars() {
-                match current_state {
-                    ParsingStates::Init => {
-                        current_state = if next_char == '[' {
-                            ParsingStates::Open
-                        } else {
-                            ParsingStates::FSpace
-                        };
-                    }
-                    ParsingStates::Open => {
-                        if couting_first_line {
-                            res_stack.insert(index, VecDeque::new());
-                        }
-
-                        res_stack[index].push_front(next_char);
-                        index += 1;
-                        current_state = ParsingStates::ObjectName;
-                    }
-                    ParsingStates::ObjectName => current_state = ParsingStates::Close,
-                    ParsingStates::Close | ParsingStates::TSpace => {
-                        current_state = ParsingStates::Init
-                    }
-                    ParsingStates::FSpace => {
-                        current_state = if next_char == ' ' {
-                            ParsingStates::SSpace
-                        } else {
-                            ParsingStates::Number
-                        }
-                    }
-                    ParsingStates::SSpace => {
-                        if couting_first_line {
-                            res_stack.insert(index, VecDeque::new());
-                        }
-                        index += 1;
-                        current_state = ParsingStates::TSpace;
-                    }
-                    ParsingStates::Number => current_state = ParsingStates::TSpace,
+        let mode = if args.iter().any(|arg| arg == "--trace") {
+            Mode::Trace
+        } else {
+            match value_of("--animate") {
+                Some(delay) => {
+                    let millis = match delay {
+                        Some(delay) => delay
+                            .parse()
+                            .map_err(|_| eyre!("Invalid animation delay '{}'", delay))?,
+                        None => 200,
+                    };
+                    Mode::Animate(Duration::from_millis(millis))
                 }
+                None => Mode::Quiet,
             }
+        };
 
-            couting_first_line = false;
-        }
+        let reach = match value_of("--reach") {
+            Some(Some(path)) => Some(path.clone()),
+            Some(None) => return Err(eyre!("--reach needs the path of a stack drawing")),
+            None => None,
+        };
+
+        let reach_limit = match value_of("--reach-limit") {
+            Some(Some(limit)) => limit
+                .parse()
+                .map_err(|_| eyre!("Invalid search limit '{}'", limit))?,
+            Some(None) => return Err(eyre!("--reach-limit needs a number of arrangements")),
+            None => MAX_SEARCH_STATES,
+        };
 
-        Ok(CargoStack { stacks: res_stack })
+        let track = match value_of("--track") {
+            Some(Some(label)) => Some(label.clone()),
+            Some(None) => return Err(eyre!("--track needs a crate label")),
+            None => None,
+        };
+
+        Ok(Options {
+            mode,
+            show_stacks: args.iter().any(|arg| arg == "--show-stacks"),
+            reach,
+            reach_limit,
+            optimize: args.iter().any(|arg| arg == "--optimize"),
+            track,
+        })
     }
 }
 
-impl std::fmt::Display for CargoStack {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (idx, el) in self.stacks.iter().enumerate() {
-            let mut comma_separated = String::new();
+fn main() -> Result<(), Report> {
+    setup()?;
 
-            for vector_element in el {
-                comma_separated.push_str(&vector_element.to_string());
-                comma_separated.push_str(", ");
-            }
+    let options = Options::from_args()?;
 
-            writeln!(f, "{} | [{}]", idx, comma_separated)?;
-        }
+    part_one(&options)?;
+    part_two(&options)?;
 
-        Ok(())
+    if let Some(path) = &options.reach {
+        reach(path, options.reach_limit)?;
     }
+    Ok(())
 }
 
-fn main() -> Result<(), Report> {
-    setup()?;
+fn part_one(options: &Options) -> Result<(), Report> {
+    println!("Part one");
+    solve(&CrateMover9000, options)
+}
 
-    part_one();
-    part_two();
-    Ok(())
+fn part_two(options: &Options) -> Result<(), Report> {
+    println!("Part two");
+    solve(&CrateMover9001, options)
 }
 
-fn part_one() -> Result<(), Report> {
-    let all_moves = get_moves_file_hanle()?
-        .lines()
-        .filter_map(|ln| ln.ok())
-        .filter_map(|ln| Move::try_from(ln).ok());
+fn solve(crane: &impl Crane, options: &Options) -> Result<(), Report> {
+    let PuzzleInput {
+        mut stack,
+        moves: original,
+    } = read_input()?;
+    let start = stack.clone();
+
+    let optimized;
+    let moves = if options.optimize {
+        optimized = optimize(&original, crane.lift_capacity());
+        if !equivalent(&stack, &original, &optimized, crane)? {
+            return Err(eyre!("Optimized procedure is not equivalent"));
+        }
 
-    let mut buf = String::new();
-    let _ = get_stack_file_handle()?.read_to_string(&mut buf)?;
-
-    if let Ok(mut stack) = CargoStack::try_from(buf) {
-        for mv in all_moves {
-            //info!("{}", &mv);
-            //info!("{}", stack);
-            for cnt in 0..mv.quantity {
-                if let Some(extracted) = stack.stacks[mv.from - 1].pop_back() {
-                    stack.stacks[mv.to - 1].push_back(extracted);
-                }
+        println!(
+            "Optimized procedure: {} -> {} moves",
+            original.len(),
+            optimized.len()
+        );
+        &optimized
+    } else {
+        &original
+    };
+    let total = moves.len();
+
+    crane.run_with(
+        &mut stack,
+        moves,
+        Strictness::Strict,
+        |number, mv, stack| match options.mode {
+            Mode::Quiet => {}
+            Mode::Trace => info!("#{} {}\n{}", number, mv, stack.to_drawing()),
+            Mode::Animate(delay) => {
+                // Clear the screen and go back to the top left corner.
+                print!("\x1b[2J\x1b[H");
+                println!("Move {}/{}: {}\n", number, total, mv);
+                println!("{}", stack.to_drawing());
+                let _ = std::io::stdout().flush();
+                thread::sleep(delay);
             }
-        }
-        println!("Part one");
+        },
+    )?;
+
+    println!("Top crates: {}", stack.top_crates());
 
-        for idx in 0..stack.stacks.len() {
-            println!("{:?}", stack.stacks[idx]);
+    if let Some(label) = &options.track {
+        // Move numbers refer to lines of the input, even when running the
+        // optimized procedure.
+        let provenance = Provenance::track(&start, &original, crane, Strictness::Strict)?;
+
+        for tracked in provenance.crates_labeled(label) {
+            print!("{}: ", tracked);
+            if let Some((idx, height)) = provenance.start_position(tracked.id) {
+                print!("starts in stack {} at height {}", idx, height + 1);
+            }
+            for hop in provenance.path(tracked.id) {
+                print!(", move {} to stack {}", hop.move_number, hop.to);
+            }
+            if let Some((idx, height)) = provenance.end_position(tracked.id) {
+                println!(", ends in stack {} at height {}", idx, height + 1);
+            }
         }
     }
+
+    if options.show_stacks {
+        println!("{}", stack.to_drawing());
+    }
     Ok(())
 }
 
-fn part_two() -> Result<(), Report> {
-    let all_moves = get_moves_file_hanle()?
-        .lines()
-        .filter_map(|ln| ln.ok())
-        .filter_map(|ln| Move::try_from(ln).ok());
-
-    let mut buf = String::new();
-    let _ = get_stack_file_handle()?.read_to_string(&mut buf)?;
-
-    if let Ok(mut stack) = CargoStack::try_from(buf) {
-        for mv in all_moves {
-            //info!("{}", &mv);
-            //info!("{}", stack);
-            let mut tmp: VecDeque<char> = VecDeque::new();
-            for cnt in 0..mv.quantity {
-                if let Some(extracted) = stack.stacks[mv.from - 1].pop_back() {
-                    tmp.push_back(extracted);
-                }
-            }
+fn reach(path: &str, limit: usize) -> Result<(), Report> {
+    let PuzzleInput { stack, .. } = read_input()?;
+    let target = CargoStack::try_from(std::fs::read_to_string(path)?)?;
+
+    println!("Reaching {}", path);
+    print_procedure(
+        "CrateMover 9000",
+        limit,
+        find_procedure(&stack, &target, &CrateMover9000, limit),
+    );
+    print_procedure(
+        "CrateMover 9001",
+        limit,
+        find_procedure(&stack, &target, &CrateMover9001, limit),
+    );
+    Ok(())
+}
 
-            for cnt in 0..mv.quantity {
-                if let Some(extracted) = tmp.pop_back() {
-                    stack.stacks[mv.to - 1].push_back(extracted);
-                }
+fn print_procedure(crane: &str, limit: usize, result: SearchResult) {
+    match result {
+        SearchResult::Found(moves) => {
+            println!("{}: {} moves", crane, moves.len());
+            for mv in moves {
+                println!("{}", mv);
             }
         }
-
-        println!("Part two");
-        for idx in 0..stack.stacks.len() {
-            println!("{:?}", stack.stacks[idx]);
+        SearchResult::Unreachable => println!("{}: unreachable", crane),
+        SearchResult::LimitReached => {
+            println!("{}: gave up after {} arrangements", crane, limit)
         }
     }
-    Ok(())
 }
 
-fn get_stack_file_handle() -> Result<BufReader<File>, std::io::Error> {
-    let file_name = "./stack.txt";
-    let input_file = File::open(file_name)?;
-    Ok(BufReader::new(input_file))
+fn read_input() -> Result<PuzzleInput, Report> {
+    let mut buf = String::new();
+    let _ = get_input_file_handle()?.read_to_string(&mut buf)?;
+
+    buf.parse().map_err(|err: String| eyre!(err))
 }
 
-fn get_moves_file_hanle() -> Result<BufReader<File>, std::io::Error> {
-    let file_name = "./moves.txt";
+fn get_input_file_handle() -> Result<BufReader<File>, std::io::Error> {
+    let file_name = "./input.txt";
     let input_file = File::open(file_name)?;
     Ok(BufReader::new(input_file))
 }
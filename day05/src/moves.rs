@@ -0,0 +1,146 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::stack::CargoStack;
+
+static REGEX_MOVES: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"move (?P<quantity>\d+) from (?P<from>\d+) to (?P<to>\d+)").unwrap());
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Move {
+    pub quantity: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.quantity, self.from, self.to
+        )
+    }
+}
+
+impl TryFrom<String> for Move {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let Some(caps) = REGEX_MOVES.captures(&value) else {
+            return Err("Connot find anything".into());
+        };
+
+        let number = |name: &str| {
+            caps[name]
+                .parse()
+                .map_err(|_| format!("{} is too large in '{}'", name, value))
+        };
+
+        Ok(Move {
+            quantity: number("quantity")?,
+            from: number("from")?,
+            to: number("to")?,
+        })
+    }
+}
+
+/// What to do when a move asks for more crates than the stack holds.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Strictness {
+    Strict,
+    /// Move whatever is there.
+    Lenient,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MoveErrorKind {
+    InvalidStack { stack: usize, stacks: usize },
+    NotEnoughCrates { requested: usize, available: usize },
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct MoveError {
+    /// 1-based position of the move in the procedure.
+    pub number: usize,
+    pub kind: MoveErrorKind,
+}
+
+impl std::fmt::Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Move {}: ", self.number)?;
+
+        match self.kind {
+            MoveErrorKind::InvalidStack { stack, stacks } => {
+                write!(f, "stack {} does not exist (there are {})", stack, stacks)
+            }
+            MoveErrorKind::NotEnoughCrates {
+                requested,
+                available,
+            } => write!(
+                f,
+                "requested {} crates but only {} available",
+                requested, available
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+impl Move {
+    /// Checks the move against the current stacks, returning the move that
+    /// will actually be executed.
+    pub fn check<T>(
+        &self,
+        stack: &CargoStack<T>,
+        strictness: Strictness,
+    ) -> Result<Move, MoveErrorKind> {
+        let stacks = stack.stacks.len();
+
+        for index in [self.from, self.to] {
+            if index == 0 || index > stacks {
+                return Err(MoveErrorKind::InvalidStack {
+                    stack: index,
+                    stacks,
+                });
+            }
+        }
+
+        let available = stack.stacks[self.from - 1].len();
+        if self.quantity <= available {
+            return Ok(*self);
+        }
+
+        match strictness {
+            Strictness::Strict => Err(MoveErrorKind::NotEnoughCrates {
+                requested: self.quantity,
+                available,
+            }),
+            Strictness::Lenient => Ok(Move {
+                quantity: available,
+                ..*self
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::moves::Move;
+
+    #[test]
+    fn wide_quantities() {
+        let mv = Move::try_from("move 1000 from 12 to 300".to_string()).unwrap();
+        assert_eq!(
+            mv,
+            Move {
+                quantity: 1000,
+                from: 12,
+                to: 300
+            }
+        );
+
+        assert!(Move::try_from("move 99999999999999999999999 from 1 to 2".to_string()).is_err());
+    }
+}
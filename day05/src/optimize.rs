@@ -0,0 +1,203 @@
+use crate::{
+    crane::Crane,
+    moves::{Move, MoveError, Strictness},
+    stack::CargoStack,
+};
+
+/// Replacement for two consecutive moves, if they can be combined.
+enum Combined {
+    Nothing,
+    One(Move),
+}
+
+fn combine(first: &Move, second: &Move, capacity: Option<usize>) -> Option<Combined> {
+    let one_at_a_time = capacity == Some(1);
+    let single_lift = capacity.is_none_or(|c| first.quantity <= c);
+
+    // Crates taken one at a time from the same stack pile up in the same
+    // order whether the move is split or not.
+    if one_at_a_time && first.from == second.from && first.to == second.to {
+        let quantity = first.quantity.checked_add(second.quantity)?;
+        return Some(Combined::One(Move { quantity, ..*first }));
+    }
+
+    // Otherwise the second move must take exactly the crates the first one
+    // just placed.
+    if first.quantity != second.quantity || first.to != second.from {
+        return None;
+    }
+
+    if second.to == first.from && (single_lift || one_at_a_time) {
+        return Some(Combined::Nothing);
+    }
+
+    if single_lift {
+        return Some(Combined::One(Move {
+            quantity: first.quantity,
+            from: first.from,
+            to: second.to,
+        }));
+    }
+
+    None
+}
+
+/// Rewrites a valid procedure into an equivalent, usually shorter, one for a
+/// crane that lifts at most `capacity` crates at a time (`None` when
+/// unlimited).
+///
+/// Removes empty moves and moves onto the same stack, cancels moves that are
+/// immediately taken back, chains moves that go through an intermediate stack
+/// in a single lift, and merges consecutive one-crate-at-a-time moves between
+/// the same stacks.
+pub fn optimize(moves: &[Move], capacity: Option<usize>) -> Vec<Move> {
+    let mut res: Vec<Move> = Vec::with_capacity(moves.len());
+
+    for mv in moves {
+        let mut current = *mv;
+
+        loop {
+            if current.quantity == 0 || current.from == current.to {
+                break;
+            }
+
+            match res
+                .last()
+                .and_then(|last| combine(last, &current, capacity))
+            {
+                Some(Combined::Nothing) => {
+                    res.pop();
+                    break;
+                }
+                Some(Combined::One(combined)) => {
+                    res.pop();
+                    current = combined;
+                }
+                None => {
+                    res.push(current);
+                    break;
+                }
+            }
+        }
+    }
+
+    res
+}
+
+/// Simulates both procedures from `stack` and tells whether they end in the
+/// same arrangement.
+pub fn equivalent(
+    stack: &CargoStack,
+    original: &[Move],
+    optimized: &[Move],
+    crane: &impl Crane,
+) -> Result<bool, MoveError> {
+    let mut first = stack.clone();
+    crane.run(&mut first, original, Strictness::Strict)?;
+
+    let mut second = stack.clone();
+    crane.run(&mut second, optimized, Strictness::Strict)?;
+
+    Ok(first == second)
+}
+
+#[cfg(test)]
+mod test {
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    use crate::{
+        crane::{Crane, CrateMover9000, CrateMover9001, LimitedCrane},
+        moves::{Move, Strictness},
+        optimize::{equivalent, optimize},
+        stack::CargoStack,
+    };
+
+    fn mv(quantity: usize, from: usize, to: usize) -> Move {
+        Move { quantity, from, to }
+    }
+
+    fn stack() -> CargoStack {
+        CargoStack::try_from("[A] [E]\n[B] [F]\n[C] [G] [I]\n[D] [H] [J]\n 1   2   3 ".to_string())
+            .unwrap()
+    }
+
+    fn check(crane: &impl Crane, moves: &[Move], expected_len: usize) {
+        let optimized = optimize(moves, crane.lift_capacity());
+
+        assert_eq!(optimized.len(), expected_len, "{:?}", optimized);
+        assert!(equivalent(&stack(), moves, &optimized, crane).unwrap());
+    }
+
+    #[test]
+    fn block_crane() {
+        check(&CrateMover9001, &[mv(2, 1, 2), mv(2, 2, 1)], 0);
+        check(&CrateMover9001, &[mv(2, 1, 2), mv(2, 2, 3)], 1);
+        check(&CrateMover9001, &[mv(0, 1, 2), mv(2, 3, 3)], 0);
+        check(&CrateMover9001, &[mv(1, 1, 2), mv(1, 1, 2)], 2);
+        check(
+            &CrateMover9001,
+            &[mv(1, 3, 1), mv(2, 1, 2), mv(2, 2, 1), mv(1, 1, 3)],
+            0,
+        );
+    }
+
+    #[test]
+    fn single_crate_crane() {
+        check(&CrateMover9000, &[mv(3, 1, 2), mv(3, 2, 1)], 0);
+        check(&CrateMover9000, &[mv(1, 1, 2), mv(2, 1, 2)], 1);
+        check(&CrateMover9000, &[mv(2, 1, 2), mv(2, 2, 3)], 2);
+    }
+
+    #[test]
+    fn limited_crane() {
+        let crane = LimitedCrane::new(2).unwrap();
+
+        check(&crane, &[mv(2, 1, 2), mv(2, 2, 1)], 0);
+        check(&crane, &[mv(3, 1, 2), mv(3, 2, 1)], 2);
+        check(&crane, &[mv(2, 2, 1), mv(2, 1, 3)], 1);
+    }
+
+    /// Random valid procedure on `stack()` biased towards moves that can be
+    /// combined.
+    fn procedure(crane: &impl Crane, g: &mut Gen) -> Vec<Move> {
+        let mut current = stack();
+        let mut moves: Vec<Move> = Vec::new();
+
+        for _ in 0..20 {
+            let next = match moves.last() {
+                Some(last) if bool::arbitrary(g) => {
+                    let to = *g.choose(&[last.from, last.to, 1, 2, 3]).unwrap();
+                    mv(last.quantity, last.to, to)
+                }
+                _ => mv(
+                    *g.choose(&[0, 1, 2, 3]).unwrap(),
+                    *g.choose(&[1, 2, 3]).unwrap(),
+                    *g.choose(&[1, 2, 3]).unwrap(),
+                ),
+            };
+
+            if crane.run(&mut current, [&next], Strictness::Strict).is_ok() {
+                moves.push(next);
+            }
+        }
+
+        moves
+    }
+
+    fn optimized_is_equivalent(crane: &impl Crane, g: &mut Gen) -> bool {
+        let moves = procedure(crane, g);
+        let optimized = optimize(&moves, crane.lift_capacity());
+
+        optimized.len() <= moves.len() && equivalent(&stack(), &moves, &optimized, crane).unwrap()
+    }
+
+    quickcheck! {
+        fn random_procedures(seed: u64) -> bool {
+            let mut g = Gen::new(seed as usize % 100 + 1);
+
+            optimized_is_equivalent(&CrateMover9000, &mut g)
+                && optimized_is_equivalent(&CrateMover9001, &mut g)
+                && optimized_is_equivalent(&LimitedCrane::new(2).unwrap(), &mut g)
+        }
+    }
+}
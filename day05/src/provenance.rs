@@ -0,0 +1,218 @@
+use std::fmt;
+
+use crate::{
+    crane::Crane,
+    moves::{Move, MoveError, Strictness},
+    stack::CargoStack,
+};
+
+/// A crate with an identity of its own, so that crates sharing a label can be
+/// told apart.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Crate {
+    pub id: usize,
+    pub label: String,
+}
+
+impl fmt::Display for Crate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#{}", self.label, self.id)
+    }
+}
+
+impl CargoStack {
+    /// Gives every crate an id, numbering stack by stack from the bottom.
+    pub fn identify(&self) -> CargoStack<Crate> {
+        let mut id = 0;
+
+        CargoStack {
+            stacks: self
+                .stacks
+                .iter()
+                .map(|stack| {
+                    stack
+                        .iter()
+                        .map(|label| {
+                            id += 1;
+                            Crate {
+                                id: id - 1,
+                                label: label.clone(),
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// One move that carried a crate.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Hop {
+    /// 1-based position of the move in the procedure.
+    pub move_number: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Where every crate went during a procedure.
+#[derive(Debug, Clone)]
+pub struct Provenance {
+    start: CargoStack<Crate>,
+    end: CargoStack<Crate>,
+    /// Hops of each crate, indexed by id.
+    paths: Vec<Vec<Hop>>,
+}
+
+impl Provenance {
+    pub fn track(
+        stack: &CargoStack,
+        moves: &[Move],
+        crane: &impl Crane,
+        strictness: Strictness,
+    ) -> Result<Self, MoveError> {
+        let start = stack.identify();
+        let mut end = start.clone();
+        let mut paths = vec![Vec::new(); start.stacks.iter().map(|s| s.len()).sum()];
+
+        crane.run_with(&mut end, moves, strictness, |move_number, mv, stack| {
+            if mv.from == mv.to {
+                return;
+            }
+
+            // Whatever the crane, the crates it moved are now on top of `to`.
+            let to = &stack.stacks[mv.to - 1];
+            for moved in &to[to.len() - mv.quantity..] {
+                paths[moved.id].push(Hop {
+                    move_number,
+                    from: mv.from,
+                    to: mv.to,
+                });
+            }
+        })?;
+
+        Ok(Provenance { start, end, paths })
+    }
+
+    /// Every crate with the given label, in id order.
+    pub fn crates_labeled(&self, label: &str) -> Vec<Crate> {
+        // Ids were given in this same order.
+        self.start
+            .stacks
+            .iter()
+            .flatten()
+            .filter(|c| c.label == label)
+            .cloned()
+            .collect()
+    }
+
+    pub fn path(&self, id: usize) -> &[Hop] {
+        self.paths.get(id).map_or(&[], |path| path)
+    }
+
+    /// 1-based stack and 0-based height of a crate, from the bottom.
+    fn locate(stack: &CargoStack<Crate>, id: usize) -> Option<(usize, usize)> {
+        stack.stacks.iter().enumerate().find_map(|(idx, s)| {
+            s.iter()
+                .position(|c| c.id == id)
+                .map(|height| (idx + 1, height))
+        })
+    }
+
+    pub fn start_position(&self, id: usize) -> Option<(usize, usize)> {
+        Self::locate(&self.start, id)
+    }
+
+    pub fn end_position(&self, id: usize) -> Option<(usize, usize)> {
+        Self::locate(&self.end, id)
+    }
+
+    pub fn end(&self) -> &CargoStack<Crate> {
+        &self.end
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        crane::{CrateMover9000, CrateMover9001},
+        input::PuzzleInput,
+        moves::Strictness,
+        provenance::{Crate, Hop, Provenance},
+    };
+
+    const EXAMPLE: &str = "[A] [A]
+[B] [A] [C]
+ 1   2   3
+
+move 1 from 1 to 3
+move 2 from 3 to 2
+move 3 from 2 to 1
+";
+
+    #[test]
+    fn duplicate_labels_are_told_apart() {
+        let input: PuzzleInput = EXAMPLE.parse().unwrap();
+        let provenance = Provenance::track(
+            &input.stack,
+            &input.moves,
+            &CrateMover9001,
+            Strictness::Strict,
+        )
+        .unwrap();
+
+        let crates = provenance.crates_labeled("A");
+        assert_eq!(
+            crates,
+            [
+                Crate {
+                    id: 1,
+                    label: "A".to_string()
+                },
+                Crate {
+                    id: 2,
+                    label: "A".to_string()
+                },
+                Crate {
+                    id: 3,
+                    label: "A".to_string()
+                }
+            ]
+        );
+
+        let hop = |move_number, from, to| Hop {
+            move_number,
+            from,
+            to,
+        };
+        assert_eq!(
+            provenance.path(1),
+            [hop(1, 1, 3), hop(2, 3, 2), hop(3, 2, 1)]
+        );
+        assert_eq!(provenance.path(2), []);
+        assert_eq!(provenance.path(3), [hop(3, 2, 1)]);
+        assert_eq!(provenance.path(4), [hop(2, 3, 2), hop(3, 2, 1)]);
+        assert_eq!(provenance.end_position(3), Some((1, 1)));
+    }
+
+    #[test]
+    fn final_labels_match_plain_run() {
+        let input: PuzzleInput = EXAMPLE.parse().unwrap();
+        let provenance = Provenance::track(
+            &input.stack,
+            &input.moves,
+            &CrateMover9000,
+            Strictness::Strict,
+        )
+        .unwrap();
+        let plain = input.solve(&CrateMover9000, Strictness::Strict).unwrap();
+
+        let labels: Vec<Vec<String>> = provenance
+            .end()
+            .stacks
+            .iter()
+            .map(|s| s.iter().map(|c| c.label.clone()).collect())
+            .collect();
+        assert_eq!(labels, plain.stacks);
+    }
+}
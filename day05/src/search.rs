@@ -0,0 +1,202 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, RandomState},
+};
+
+use crate::{crane::Crane, moves::Move, stack::CargoStack};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SearchResult {
+    /// A shortest procedure leading to the target.
+    Found(Vec<Move>),
+    Unreachable,
+    /// Gave up after exploring the maximum number of arrangements.
+    LimitReached,
+}
+
+/// Same number of stacks and same crates, regardless of where they are.
+fn same_crates(first: &CargoStack, second: &CargoStack) -> bool {
+    fn crates(stack: &CargoStack) -> Vec<&String> {
+        let mut res: Vec<&String> = stack.stacks.iter().flatten().collect();
+        res.sort_unstable();
+        res
+    }
+
+    first.stacks.len() == second.stacks.len() && crates(first) == crates(second)
+}
+
+fn candidate_moves<T>(stack: &CargoStack<T>) -> impl Iterator<Item = Move> + '_ {
+    let stacks = stack.stacks.len();
+
+    (1..=stacks).flat_map(move |from| {
+        let available = stack.stacks[from - 1].len();
+
+        (1..=stacks)
+            .filter(move |&to| to != from)
+            .flat_map(move |to| (1..=available).map(move |quantity| Move { quantity, from, to }))
+    })
+}
+
+/// Marks the end of a stack in an encoded arrangement.
+const END_OF_STACK: u16 = u16::MAX;
+
+/// Compact copy of an arrangement, with crates replaced by ids and stacks
+/// laid out one after the other.
+type Encoded = Box<[u16]>;
+
+fn encode(stack: &CargoStack<u16>) -> Encoded {
+    stack
+        .stacks
+        .iter()
+        .flat_map(|s| s.iter().copied().chain([END_OF_STACK]))
+        .collect()
+}
+
+fn decode(encoded: &[u16]) -> CargoStack<u16> {
+    CargoStack {
+        stacks: encoded
+            .split_inclusive(|&id| id == END_OF_STACK)
+            .map(|s| s[..s.len() - 1].to_vec())
+            .collect(),
+    }
+}
+
+/// Breadth-first search for the shortest procedure that turns `start` into
+/// `target` with the given crane, exploring at most `max_states` arrangements.
+///
+/// Every arrangement is kept once, encoded, and looked up by its hash.
+pub fn find_procedure(
+    start: &CargoStack,
+    target: &CargoStack,
+    crane: &impl Crane,
+    max_states: usize,
+) -> SearchResult {
+    if start == target {
+        return SearchResult::Found(Vec::new());
+    }
+    if !same_crates(start, target) {
+        return SearchResult::Unreachable;
+    }
+
+    let mut labels: Vec<&String> = start.stacks.iter().flatten().collect();
+    labels.sort_unstable();
+    labels.dedup();
+    // Too many different crates to give them ids, and to ever search through.
+    if labels.len() >= usize::from(END_OF_STACK) {
+        return SearchResult::LimitReached;
+    }
+    let ids = |stack: &CargoStack| CargoStack {
+        stacks: stack
+            .stacks
+            .iter()
+            .map(|s| {
+                s.iter()
+                    .map(|label| labels.binary_search(&label).unwrap() as u16)
+                    .collect()
+            })
+            .collect(),
+    };
+    let target = encode(&ids(target));
+
+    let hasher = RandomState::new();
+    let mut states: Vec<Encoded> = vec![encode(&ids(start))];
+    // Index of the previous arrangement and the move that left it.
+    let mut parents: Vec<Option<(usize, Move)>> = vec![None];
+    // Indexes of the arrangements with a given hash.
+    let mut seen: HashMap<u64, Vec<usize>> =
+        HashMap::from([(hasher.hash_one(&states[0]), vec![0])]);
+    let mut next = 0;
+
+    while next < states.len() {
+        let current = decode(&states[next]);
+
+        for mv in candidate_moves(&current) {
+            let mut candidate = current.clone();
+            crane.apply(&mut candidate, &mv);
+            let candidate = encode(&candidate);
+
+            let same_hash = seen.entry(hasher.hash_one(&candidate)).or_default();
+            if same_hash.iter().any(|&idx| states[idx] == candidate) {
+                continue;
+            }
+
+            parents.push(Some((next, mv)));
+
+            if candidate == target {
+                let mut procedure = Vec::new();
+                let mut current = parents.len() - 1;
+
+                while let Some((parent, mv)) = parents[current] {
+                    procedure.push(mv);
+                    current = parent;
+                }
+
+                procedure.reverse();
+                return SearchResult::Found(procedure);
+            }
+
+            if states.len() >= max_states {
+                return SearchResult::LimitReached;
+            }
+            same_hash.push(states.len());
+            states.push(candidate);
+        }
+
+        next += 1;
+    }
+
+    SearchResult::Unreachable
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        crane::{Crane, CrateMover9000, CrateMover9001},
+        moves::Strictness,
+        search::{find_procedure, SearchResult},
+        stack::CargoStack,
+    };
+
+    fn stack(drawing: &str) -> CargoStack {
+        CargoStack::try_from(drawing.to_string()).unwrap()
+    }
+
+    #[test]
+    fn shortest_procedures() {
+        let start = stack("[A]\n[B]\n[C]\n 1   2   3 ");
+        let target = stack("    [A]\n    [B]\n    [C]\n 1   2   3 ");
+
+        let SearchResult::Found(block) = find_procedure(&start, &target, &CrateMover9001, 1000)
+        else {
+            panic!("target should be reachable");
+        };
+        assert_eq!(block.len(), 1);
+
+        let SearchResult::Found(single) = find_procedure(&start, &target, &CrateMover9000, 1000)
+        else {
+            panic!("target should be reachable");
+        };
+        // Reversing twice through the third stack.
+        assert_eq!(single.len(), 2);
+
+        let mut replay = start.clone();
+        CrateMover9000
+            .run(&mut replay, &single, Strictness::Strict)
+            .unwrap();
+        assert_eq!(replay, target);
+    }
+
+    #[test]
+    fn unreachable_targets() {
+        let start = stack("[A]\n[B]\n 1   2 ");
+
+        assert_eq!(
+            find_procedure(&start, &stack("[A]\n[C]\n 1   2 "), &CrateMover9001, 1000),
+            SearchResult::Unreachable
+        );
+        assert_eq!(
+            find_procedure(&start, &stack("[A] [B]\n 1   2 "), &CrateMover9001, 1),
+            SearchResult::LimitReached
+        );
+    }
+}
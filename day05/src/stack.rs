@@ -0,0 +1,447 @@
+/// Stacks of crates, each listed from the bottom to the top.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CargoStack<T = String> {
+    pub stacks: Vec<Vec<T>>,
+}
+
+impl<T> CargoStack<T> {
+    /// Moves the top `quantity` crates of stack `from` onto stack `to` (both
+    /// 0-based) as a single block. With `reverse` the block is flipped, as if
+    /// the crates were moved one at a time.
+    pub fn transfer(&mut self, from: usize, to: usize, quantity: usize, reverse: bool) {
+        // Crates lifted and put back on the same stack end up where they were.
+        if from == to {
+            return;
+        }
+
+        let at = self.stacks[from].len().saturating_sub(quantity);
+
+        let (source, target) = if from < to {
+            let (left, right) = self.stacks.split_at_mut(to);
+            (&mut left[from], &mut right[0])
+        } else {
+            let (left, right) = self.stacks.split_at_mut(from);
+            (&mut right[0], &mut left[to])
+        };
+
+        let start = target.len();
+        target.extend(source.drain(at..));
+
+        if reverse {
+            target[start..].reverse();
+        }
+    }
+}
+
+impl<T: Clone> CargoStack<T> {
+    /// Crate on top of each stack, `None` for empty stacks.
+    pub fn tops(&self) -> Vec<Option<T>> {
+        self.stacks.iter().map(|s| s.last().cloned()).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DrawingErrorKind {
+    MissingFooter,
+    /// Footer token that isn't the next stack number.
+    BadLabel {
+        expected: usize,
+    },
+    MalformedCell,
+    /// Crate whose name isn't under any footer label.
+    OutsideStack,
+    /// Crate whose name is under more than one footer label.
+    SeveralStacks,
+    /// Empty slot with a crate above it.
+    FloatingCrate,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DrawingError {
+    /// 1-based line of the drawing.
+    pub line: usize,
+    /// 1-based character column.
+    pub column: usize,
+    pub kind: DrawingErrorKind,
+}
+
+impl std::fmt::Display for DrawingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            DrawingErrorKind::MissingFooter => write!(f, "Missing stack numbers line")?,
+            DrawingErrorKind::BadLabel { expected } => {
+                write!(f, "Expected stack number {}", expected)?
+            }
+            DrawingErrorKind::MalformedCell => write!(f, "Malformed crate")?,
+            DrawingErrorKind::OutsideStack => write!(f, "Crate is not under any stack number")?,
+            DrawingErrorKind::SeveralStacks => {
+                write!(f, "Crate is above more than one stack number")?
+            }
+            DrawingErrorKind::FloatingCrate => write!(f, "Empty slot below a crate")?,
+        }
+
+        write!(f, " at line {}, column {}", self.line, self.column)
+    }
+}
+
+impl std::error::Error for DrawingError {}
+
+/// Column span `(first, last)` of each stack number in the footer line.
+fn parse_footer(footer: &[char], line: usize) -> Result<Vec<(usize, usize)>, DrawingError> {
+    let mut labels = Vec::new();
+    let mut col = 0;
+
+    while col < footer.len() {
+        if footer[col].is_whitespace() {
+            col += 1;
+            continue;
+        }
+
+        let start = col;
+        while col < footer.len() && !footer[col].is_whitespace() {
+            col += 1;
+        }
+
+        let expected = labels.len() + 1;
+        let token: String = footer[start..col].iter().collect();
+        if token.parse::<usize>().ok() != Some(expected) {
+            return Err(DrawingError {
+                line,
+                column: start + 1,
+                kind: DrawingErrorKind::BadLabel { expected },
+            });
+        }
+
+        labels.push((start, col - 1));
+    }
+
+    Ok(labels)
+}
+
+impl TryFrom<String> for CargoStack {
+    type Error = DrawingError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let lines: Vec<Vec<char>> = value
+            .trim_end()
+            .lines()
+            .map(|ln| ln.chars().collect())
+            .collect();
+
+        let Some((footer, rows)) = lines.split_last() else {
+            return Err(DrawingError {
+                line: 1,
+                column: 1,
+                kind: DrawingErrorKind::MissingFooter,
+            });
+        };
+
+        let labels = parse_footer(footer, lines.len())?;
+        let mut res_stack: Vec<Vec<String>> = vec![Vec::new(); labels.len()];
+
+        for (line_idx, row) in rows.iter().enumerate() {
+            let error = |col: usize, kind| DrawingError {
+                line: line_idx + 1,
+                column: col + 1,
+                kind,
+            };
+            let mut filled = vec![false; labels.len()];
+            let mut col = 0;
+
+            while col < row.len() {
+                match row[col] {
+                    ' ' => col += 1,
+                    '[' => {
+                        let close = (col + 1..row.len())
+                            .find(|&idx| row[idx] == ']' || row[idx].is_whitespace())
+                            .filter(|&idx| row[idx] == ']' && idx > col + 1);
+                        let Some(close) = close else {
+                            return Err(error(col, DrawingErrorKind::MalformedCell));
+                        };
+
+                        // The label must sit above exactly one stack number.
+                        let mut under = (0..labels.len()).filter(|&idx| {
+                            let (first, last) = labels[idx];
+                            first < close && col < last
+                        });
+                        let Some(index) = under.next() else {
+                            return Err(error(col + 1, DrawingErrorKind::OutsideStack));
+                        };
+                        if under.next().is_some() {
+                            return Err(error(col + 1, DrawingErrorKind::SeveralStacks));
+                        }
+                        if filled[index] {
+                            return Err(error(col, DrawingErrorKind::MalformedCell));
+                        }
+
+                        res_stack[index].push(row[col + 1..close].iter().collect());
+                        filled[index] = true;
+                        col = close + 1;
+                    }
+                    _ => return Err(error(col, DrawingErrorKind::MalformedCell)),
+                }
+            }
+
+            // Every stack that already has a crate above must continue down to the floor.
+            for (index, stack) in res_stack.iter().enumerate() {
+                if !filled[index] && !stack.is_empty() {
+                    return Err(error(labels[index].0, DrawingErrorKind::FloatingCrate));
+                }
+            }
+        }
+
+        // Rows were read top to bottom.
+        for stack in res_stack.iter_mut() {
+            stack.reverse();
+        }
+
+        Ok(CargoStack { stacks: res_stack })
+    }
+}
+
+impl CargoStack {
+    /// The puzzle answer: the top crate of every stack, with a space standing
+    /// in for empty stacks so that positions are preserved. When some label is
+    /// longer than one character, the tops are separated by spaces instead
+    /// and empty stacks are left blank.
+    pub fn top_crates(&self) -> String {
+        let single = self.stacks.iter().flatten().all(|c| c.chars().count() == 1);
+        let tops = self.stacks.iter().map(|s| s.last().map(String::as_str));
+
+        if single {
+            tops.map(|top| top.unwrap_or(" ")).collect()
+        } else {
+            tops.map(|top| top.unwrap_or(""))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+    }
+
+    /// Width of a column of the drawing: wide enough for the longest crate
+    /// label with its brackets and for the highest stack number.
+    fn column_width(&self) -> usize {
+        let label = self
+            .stacks
+            .iter()
+            .flatten()
+            .map(|c| c.chars().count())
+            .max();
+
+        (label.unwrap_or(1) + 2).max(self.stacks.len().to_string().len())
+    }
+
+    /// Writes the stacks the way the puzzle input draws them: one row per
+    /// level padded to the full width, then the numbered footer. Crates and
+    /// numbers are centered in their columns, which are 3 characters wide
+    /// unless longer labels need more room. Parsing the output gives back the
+    /// same stacks.
+    pub fn write_drawing(&self, out: &mut impl std::fmt::Write) -> std::fmt::Result {
+        let height = self.stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+        let width = self.column_width();
+
+        for level in (0..height).rev() {
+            for (idx, stack) in self.stacks.iter().enumerate() {
+                if idx > 0 {
+                    out.write_char(' ')?;
+                }
+
+                match stack.get(level) {
+                    Some(name) => write!(out, "{:^width$}", format!("[{}]", name))?,
+                    None => write!(out, "{:width$}", "")?,
+                }
+            }
+            out.write_char('\n')?;
+        }
+
+        for label in 1..=self.stacks.len() {
+            if label > 1 {
+                out.write_char(' ')?;
+            }
+            write!(out, "{:^width$}", label)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn to_drawing(&self) -> String {
+        let mut res = String::new();
+        self.write_drawing(&mut res)
+            .expect("writing to a String cannot fail");
+        res
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for CargoStack<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, el) in self.stacks.iter().enumerate() {
+            let mut comma_separated = String::new();
+
+            for vector_element in el {
+                comma_separated.push_str(&vector_element.to_string());
+                comma_separated.push_str(", ");
+            }
+
+            writeln!(f, "{} | [{}]", idx, comma_separated)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    use crate::stack::{CargoStack, DrawingError, DrawingErrorKind};
+
+    impl Arbitrary for CargoStack {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let names: Vec<String> = ["A", "Z", "#", "[", "7", "AB", "CRATE", "x1y"]
+                .map(String::from)
+                .to_vec();
+            let stacks = 1 + usize::arbitrary(g) % 120;
+
+            CargoStack {
+                stacks: (0..stacks)
+                    .map(|_| {
+                        (0..usize::arbitrary(g) % 6)
+                            .map(|_| g.choose(&names).unwrap().clone())
+                            .collect()
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    quickcheck! {
+        fn drawing_round_trip(stack: CargoStack) -> bool {
+            CargoStack::try_from(stack.to_drawing()) == Ok(stack)
+        }
+    }
+
+    fn parse(drawing: &str) -> Result<CargoStack, DrawingError> {
+        CargoStack::try_from(drawing.to_string())
+    }
+
+    #[test]
+    fn stripped_trailing_whitespace() {
+        let stack = parse("    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3").unwrap();
+
+        assert_eq!(stack.stacks.len(), 3);
+        assert_eq!(stack.stacks[0], ["Z", "N"]);
+        assert_eq!(stack.stacks[1], ["M", "C", "D"]);
+        assert_eq!(stack.stacks[2], ["P"]);
+    }
+
+    #[test]
+    fn stacks_from_footer_only() {
+        let stack = parse("[A]\n 1   2   3 ").unwrap();
+
+        assert_eq!(stack.stacks.len(), 3);
+        assert!(stack.stacks[1].is_empty() && stack.stacks[2].is_empty());
+    }
+
+    #[test]
+    fn multi_digit_labels() {
+        let drawing = concat!(
+            "                                        [K]\n",
+            "[A] [B] [C] [D] [E] [F] [G] [H] [I] [J] [K]\n",
+            " 1   2   3   4   5   6   7   8   9   10  11"
+        );
+        let stack = parse(drawing).unwrap();
+
+        assert_eq!(stack.stacks.len(), 11);
+        assert_eq!(stack.stacks[9], ["J"]);
+        assert_eq!(stack.stacks[10], ["K", "K"]);
+    }
+
+    #[test]
+    fn positioned_errors() {
+        let kind_at = |drawing, line, column| {
+            let err = parse(drawing).unwrap_err();
+            assert_eq!((err.line, err.column), (line, column));
+            err.kind
+        };
+
+        assert_eq!(
+            kind_at("[A] [B\n 1   2 ", 1, 5),
+            DrawingErrorKind::MalformedCell
+        );
+        assert_eq!(
+            kind_at("[A] x\n 1   2 ", 1, 5),
+            DrawingErrorKind::MalformedCell
+        );
+        assert_eq!(
+            kind_at("[A]\n    [B]\n 1   2 ", 2, 2),
+            DrawingErrorKind::FloatingCrate
+        );
+        assert_eq!(
+            kind_at("  [A]\n 1   2 ", 1, 4),
+            DrawingErrorKind::OutsideStack
+        );
+        assert_eq!(
+            kind_at("[ABC]\n 1 2 ", 1, 2),
+            DrawingErrorKind::SeveralStacks
+        );
+        assert_eq!(
+            kind_at("[A]\n 1   3 ", 2, 6),
+            DrawingErrorKind::BadLabel { expected: 2 }
+        );
+    }
+
+    #[test]
+    fn block_transfers() {
+        let mut stack = parse("[A]\n[B]\n[C] [D]\n 1   2 ").unwrap();
+
+        stack.transfer(0, 1, 2, false);
+        assert_eq!(stack.stacks, [vec!["C"], vec!["D", "B", "A"]]);
+
+        stack.transfer(1, 0, 2, true);
+        assert_eq!(stack.stacks, [vec!["C", "A", "B"], vec!["D"]]);
+
+        // Putting crates back onto the stack they came from changes nothing.
+        let before = stack.clone();
+        stack.transfer(0, 0, 3, true);
+        assert_eq!(stack, before);
+    }
+
+    #[test]
+    fn drawing_format() {
+        let drawing = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ";
+
+        assert_eq!(parse(drawing).unwrap().to_drawing(), drawing);
+    }
+
+    #[test]
+    fn top_crates() {
+        let stack = parse("[A]     [C]\n[B]     [D]\n 1   2   3 ").unwrap();
+
+        assert_eq!(
+            stack.tops(),
+            [Some("A".to_string()), None, Some("C".to_string())]
+        );
+        assert_eq!(stack.top_crates(), "A C");
+    }
+
+    #[test]
+    fn multi_character_labels() {
+        let drawing = concat!(
+            "[AB]             \n",
+            "[CDE]  [F]  [GH] \n",
+            "  1     2     3  "
+        );
+        let stack = parse(drawing).unwrap();
+
+        assert_eq!(stack.stacks[0], ["CDE", "AB"]);
+        assert_eq!(stack.stacks[1], ["F"]);
+        assert_eq!(stack.stacks[2], ["GH"]);
+        assert_eq!(stack.top_crates(), "AB F GH");
+
+        let other = parse("         [GH]\n[A] [BF] [GH]\n 1   2    3  ").unwrap();
+        assert_eq!(other.top_crates(), "A BF GH");
+
+        let empty = parse("[AB]      \n 1    2   ").unwrap();
+        assert_eq!(empty.top_crates(), "AB ");
+        assert_eq!(stack.to_drawing(), drawing);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::marker::find_marker;
+
+/// Number of prefix masks kept at a time by `find_marker_simd`.
+#[cfg(target_arch = "x86_64")]
+const BLOCK_SIZE: usize = 1024;
+
+/// One bit for each different byte, given as the bytes show up, as long as
+/// there are at most 64 of them.
+///
+/// A window is a marker when XOR-ing the bits of its bytes sets exactly `len`
+/// bits: bytes seen twice cancel out.
+struct Alphabet {
+    bits: [u64; 256],
+    symbols: u32,
+}
+
+impl Alphabet {
+    fn new() -> Self {
+        Alphabet {
+            bits: [0; 256],
+            symbols: 0,
+        }
+    }
+
+    /// Bit of `byte`, `None` if it would be the 65th different byte.
+    fn bit(&mut self, byte: u8) -> Option<u64> {
+        let bit = &mut self.bits[usize::from(byte)];
+        if *bit == 0 {
+            if self.symbols == u64::BITS {
+                return None;
+            }
+            *bit = 1 << self.symbols;
+            self.symbols += 1;
+        }
+
+        Some(*bit)
+    }
+}
+
+/// `find_marker` for the windows ending at `end` or later.
+fn find_marker_from(signal: &[u8], len: usize, end: usize) -> Option<usize> {
+    let start = end.saturating_sub(len);
+
+    find_marker(&signal[start..], len).map(|idx| start + idx)
+}
+
+/// Same as `find_marker`, keeping the window as a bitmask while the signal
+/// uses at most 64 different bytes.
+pub fn find_marker_bitmask(signal: &[u8], len: usize) -> Option<usize> {
+    if len == 0 {
+        return Some(0);
+    }
+    if len > u64::BITS as usize {
+        return find_marker(signal, len);
+    }
+
+    let mut alphabet = Alphabet::new();
+    let mut mask = 0;
+
+    for (idx, &byte) in signal.iter().enumerate() {
+        let Some(bit) = alphabet.bit(byte) else {
+            return find_marker_from(signal, len, idx + 1);
+        };
+
+        mask ^= bit;
+        if idx >= len {
+            mask ^= alphabet.bits[usize::from(signal[idx - len])];
+        }
+
+        if idx + 1 >= len && mask.count_ones() as usize == len {
+            return Some(idx + 1);
+        }
+    }
+
+    None
+}
+
+/// Same as `find_marker_bitmask`, testing four windows at a time with AVX2
+/// when the processor supports it.
+pub fn find_marker_simd(signal: &[u8], len: usize) -> Option<usize> {
+    #[cfg(target_arch = "x86_64")]
+    if (1..=u64::BITS as usize).contains(&len) && is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 is available.
+        return unsafe { find_marker_avx2(signal, len) };
+    }
+
+    find_marker_bitmask(signal, len)
+}
+
+/// `find_marker_simd` for `len` between 1 and 64, one block of prefix masks
+/// at a time.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn find_marker_avx2(signal: &[u8], len: usize) -> Option<usize> {
+    let mut alphabet = Alphabet::new();
+    let mut mask = 0;
+
+    // `prefix[i]` is the mask of the first `base + i` bytes, so the mask of
+    // the window ending at `base + end` is `prefix[end] ^ prefix[end - len]`.
+    let mut prefix = [0; BLOCK_SIZE];
+    let mut base = 0;
+    let mut filled = 1;
+    let mut bytes = signal.iter().enumerate();
+
+    loop {
+        let mut overflow = None;
+        while filled < BLOCK_SIZE {
+            let Some((idx, &byte)) = bytes.next() else {
+                break;
+            };
+            let Some(bit) = alphabet.bit(byte) else {
+                overflow = Some(idx);
+                break;
+            };
+
+            mask ^= bit;
+            prefix[filled] = mask;
+            filled += 1;
+        }
+
+        if let Some(end) = x86::find_full_window(&prefix[..filled], len) {
+            return Some(base + end);
+        }
+        if let Some(idx) = overflow {
+            return find_marker_from(signal, len, idx + 1);
+        }
+        if filled < BLOCK_SIZE {
+            return None;
+        }
+
+        // Windows ending in the next block start at most `len` bytes earlier.
+        prefix.copy_within(filled - len..filled, 0);
+        base += filled - len;
+        filled = len;
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    /// First `end` from `len` on for which `prefix[end] ^ prefix[end - len]`
+    /// has `len` bits set. Bits are counted with a lookup table on each half
+    /// byte.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn find_full_window(prefix: &[u64], len: usize) -> Option<usize> {
+        let nibble_bits = _mm256_setr_epi8(
+            0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, //
+            0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4,
+        );
+        let low_nibble = _mm256_set1_epi8(0x0f);
+        let target = _mm256_set1_epi64x(len as i64);
+
+        let mut end = len;
+        while end + 4 <= prefix.len() {
+            let last = _mm256_loadu_si256(prefix.as_ptr().add(end).cast());
+            let first = _mm256_loadu_si256(prefix.as_ptr().add(end - len).cast());
+            let mask = _mm256_xor_si256(last, first);
+
+            let low = _mm256_and_si256(mask, low_nibble);
+            let high = _mm256_and_si256(_mm256_srli_epi16(mask, 4), low_nibble);
+            let counts = _mm256_add_epi8(
+                _mm256_shuffle_epi8(nibble_bits, low),
+                _mm256_shuffle_epi8(nibble_bits, high),
+            );
+            // Adds up the byte counts of each 64-bit lane.
+            let sums = _mm256_sad_epu8(counts, _mm256_setzero_si256());
+
+            let found = _mm256_movemask_pd(_mm256_castsi256_pd(_mm256_cmpeq_epi64(sums, target)));
+            if found != 0 {
+                return Some(end + found.trailing_zeros() as usize);
+            }
+            end += 4;
+        }
+
+        (end..prefix.len())
+            .find(|&end| (prefix[end] ^ prefix[end - len]).count_ones() as usize == len)
+    }
+}
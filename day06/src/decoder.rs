@@ -0,0 +1,129 @@
+use std::ops::Range;
+
+use crate::marker::find_marker;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SegmentKind {
+    Packet,
+    Message,
+}
+
+/// A part of the signal introduced by a marker.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Segment {
+    pub kind: SegmentKind,
+    /// The distinct bytes announcing the segment.
+    pub marker: Range<usize>,
+    /// What follows the marker, up to the next marker or the end.
+    pub data: Range<usize>,
+}
+
+/// Splits a signal into packets, each one followed by its message.
+///
+/// A packet starts after `packet_len` distinct bytes, its message after the
+/// next `message_len` distinct bytes, then the next packet after the next
+/// `packet_len` distinct ones, and so on. A marker is looked for only after
+/// the previous one, never overlapping it. Decoding stops at an empty marker,
+/// which would otherwise split the signal forever.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Decoder {
+    pub packet_len: usize,
+    pub message_len: usize,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Decoder {
+            packet_len: 4,
+            message_len: 14,
+        }
+    }
+}
+
+impl Decoder {
+    pub fn segments(&self, signal: &[u8]) -> Vec<Segment> {
+        let mut res: Vec<Segment> = Vec::new();
+        let mut kind = SegmentKind::Packet;
+        let mut pos = 0;
+
+        loop {
+            let len = match kind {
+                SegmentKind::Packet => self.packet_len,
+                SegmentKind::Message => self.message_len,
+            };
+            if len == 0 {
+                break;
+            }
+            let Some(end) = find_marker(&signal[pos..], len).map(|idx| pos + idx) else {
+                break;
+            };
+
+            if let Some(last) = res.last_mut() {
+                last.data.end = end - len;
+            }
+            res.push(Segment {
+                kind,
+                marker: end - len..end,
+                data: end..signal.len(),
+            });
+
+            pos = end;
+            kind = match kind {
+                SegmentKind::Packet => SegmentKind::Message,
+                SegmentKind::Message => SegmentKind::Packet,
+            };
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::decoder::{Decoder, Segment, SegmentKind};
+
+    #[test]
+    fn packets_and_messages() {
+        let signal = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        let segments = Decoder::default().segments(signal);
+
+        assert_eq!(
+            segments,
+            [
+                Segment {
+                    kind: SegmentKind::Packet,
+                    marker: 3..7,
+                    data: 7..11
+                },
+                Segment {
+                    kind: SegmentKind::Message,
+                    marker: 11..25,
+                    data: 25..25
+                },
+                Segment {
+                    kind: SegmentKind::Packet,
+                    marker: 25..29,
+                    data: 29..30
+                },
+            ]
+        );
+        assert_eq!(&signal[segments[1].marker.clone()], b"sphdztnvjfqwrc");
+    }
+
+    #[test]
+    fn no_markers() {
+        let decoder = Decoder {
+            packet_len: 2,
+            message_len: 3,
+        };
+
+        assert_eq!(decoder.segments(b"aaaa"), []);
+        assert_eq!(decoder.segments(b"abba").len(), 1);
+
+        let decoder = Decoder {
+            packet_len: 2,
+            message_len: 0,
+        };
+        assert_eq!(decoder.segments(b"abcd").len(), 1);
+    }
+}
@@ -1,141 +1,57 @@
+use day06::{decoder::Decoder, stream::read_markers};
 use std::{
-    collections::HashSet,
     fs::File,
     io::{BufReader, Read},
 };
 
 fn main() -> Result<(), std::io::Error> {
-    part_one()?;
+    let markers = read_markers(get_input_handle()?, &[4, 14])?;
 
-    part_two()?;
+    part_one(markers[0]);
+
+    part_two(markers[1]);
+
+    if std::env::args().any(|arg| arg == "--segments") {
+        segments()?;
+    }
 
     Ok(())
 }
 
-fn part_one() -> Result<(), std::io::Error> {
+fn part_one(marker: Option<usize>) {
     println!("Part one");
 
-    let mut buf: String = String::new();
-    let _ = get_input_handle()?.read_to_string(&mut buf)?;
-
-    if let Some(idx) = get_first_different_sequence_index(&buf, 4) {
+    if let Some(idx) = marker {
         println!("Starting at: {}", idx);
     }
-
-    Ok(())
 }
 
-fn part_two() -> Result<(), std::io::Error> {
+fn part_two(marker: Option<usize>) {
     println!("Part two");
 
-    let mut buf: String = String::new();
-    let _ = get_input_handle()?.read_to_string(&mut buf)?;
-
-    if let Some(idx) = get_first_different_sequence_index(&buf, 14) {
+    if let Some(idx) = marker {
         println!("Starting at: {}", idx);
     }
-
-    Ok(())
-}
-
-fn get_input_handle() -> Result<BufReader<File>, std::io::Error> {
-    let f = File::open("./input.txt")?;
-
-    Ok(BufReader::new(f))
 }
 
-fn get_first_different_sequence_index(signal: &str, len: usize) -> Option<usize> {
-    if signal.len() < len - 1 {
-        return None;
-    }
-
-    for idx in len..signal.len() {
-        let sl = &signal[idx - len..idx];
-
-        let mut uniq = HashSet::new();
-        let uniq = sl.chars().all(|x| uniq.insert(x));
+fn segments() -> Result<(), std::io::Error> {
+    let mut buf = Vec::new();
+    let _ = get_input_handle()?.read_to_end(&mut buf)?;
 
-        if uniq {
-            return Some(idx);
-        }
+    for segment in Decoder::default().segments(&buf) {
+        println!(
+            "{:?} marker at {:?}, {} bytes of data",
+            segment.kind,
+            segment.marker,
+            segment.data.len()
+        );
     }
 
-    None
+    Ok(())
 }
 
-#[cfg(test)]
-mod test {
-    use crate::get_first_different_sequence_index;
-
-    #[test]
-    fn part_one_test_1() {
-        let s = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-        let r = get_first_different_sequence_index(s, 4);
-
-        assert_eq!(r, Some(5));
-    }
-
-    #[test]
-    fn part_one_test_2() {
-        let s = "nppdvjthqldpwncqszvftbrmjlhg";
-        let r = get_first_different_sequence_index(s, 4);
-
-        assert_eq!(r, Some(6));
-    }
-
-    #[test]
-    fn part_one_test_3() {
-        let s = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-        let r = get_first_different_sequence_index(s, 4);
-
-        assert_eq!(r, Some(10));
-    }
-
-    #[test]
-    fn part_one_test_4() {
-        let s = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
-        let r = get_first_different_sequence_index(s, 4);
-
-        assert_eq!(r, Some(11));
-    }
-
-    #[test]
-    fn part_two_test_1() {
-        let s = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
-        let r = get_first_different_sequence_index(s, 14);
-
-        assert_eq!(r, Some(19));
-    }
-
-    #[test]
-    fn part_two_test_2() {
-        let s = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-        let r = get_first_different_sequence_index(s, 14);
-
-        assert_eq!(r, Some(23));
-    }
-
-    #[test]
-    fn part_two_test_3() {
-        let s = "nppdvjthqldpwncqszvftbrmjlhg";
-        let r = get_first_different_sequence_index(s, 14);
-
-        assert_eq!(r, Some(23));
-    }
-
-    #[test]
-    fn part_two_test_4() {
-        let s = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-        let r = get_first_different_sequence_index(s, 14);
-
-        assert_eq!(r, Some(29));
-    }
-
-    #[test]
-    fn part_two_test_5() {
-        let s = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
-        let r = get_first_different_sequence_index(s, 14);
+fn get_input_handle() -> Result<BufReader<File>, std::io::Error> {
+    let f = File::open("./input.txt")?;
 
-        assert_eq!(r, Some(26));
-    }
+    Ok(BufReader::new(f))
 }
@@ -0,0 +1,398 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    bitmask::{find_marker_bitmask, find_marker_simd},
+    stream::MarkerDetector,
+};
+
+/// Number of chars up to the end of the first `len` consecutive distinct
+/// chars, checking every window on its own.
+pub fn get_first_different_sequence_index(signal: &str, len: usize) -> Option<usize> {
+    let chars: Vec<char> = signal.chars().collect();
+
+    if len == 0 {
+        return Some(0);
+    }
+
+    for idx in len..=chars.len() {
+        let sl = &chars[idx - len..idx];
+
+        let mut uniq = HashSet::new();
+        let uniq = sl.iter().all(|x| uniq.insert(x));
+
+        if uniq {
+            return Some(idx);
+        }
+    }
+
+    None
+}
+
+/// Position right after the first `len` consecutive distinct bytes. An empty
+/// window is found right at the start.
+///
+/// Keeps the window of distinct bytes ending at the current position and,
+/// when a byte repeats, moves its start right past the previous occurrence
+/// instead of checking every window again.
+pub fn find_marker(signal: &[u8], len: usize) -> Option<usize> {
+    if len == 0 {
+        return Some(0);
+    }
+
+    // 1-based position of the last occurrence of each byte, 0 if not seen yet.
+    let mut last_seen = [0; 256];
+    let mut start = 0;
+
+    for (idx, &byte) in signal.iter().enumerate() {
+        start = start.max(last_seen[usize::from(byte)]);
+        last_seen[usize::from(byte)] = idx + 1;
+
+        if idx + 1 - start == len {
+            return Some(idx + 1);
+        }
+    }
+
+    None
+}
+
+/// Same as `find_marker` for any kind of symbol.
+fn find_distinct<T: Eq + Hash>(symbols: impl IntoIterator<Item = T>, len: usize) -> Option<usize> {
+    if len == 0 {
+        return Some(0);
+    }
+
+    let mut last_seen = HashMap::new();
+    let mut start = 0;
+
+    for (idx, symbol) in symbols.into_iter().enumerate() {
+        if let Some(previous) = last_seen.insert(symbol, idx + 1) {
+            start = start.max(previous);
+        }
+
+        if idx + 1 - start == len {
+            return Some(idx + 1);
+        }
+    }
+
+    None
+}
+
+/// Like `find_marker` for text, counting chars instead of bytes.
+pub fn find_char_marker(signal: &str, len: usize) -> Option<usize> {
+    find_distinct(signal.chars(), len)
+}
+
+/// Like `find_marker` for text, counting user-perceived characters, so that
+/// a letter and its combining accents are a single symbol.
+pub fn find_grapheme_marker(signal: &str, len: usize) -> Option<usize> {
+    find_distinct(signal.graphemes(true), len)
+}
+
+/// How far from fully distinct a window can be and still be a marker.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Tolerance {
+    /// At least this many different bytes.
+    MinDistinct(usize),
+    /// At most this many occurrences of bytes already in the window.
+    MaxDuplicates(usize),
+}
+
+impl Tolerance {
+    /// Whether a window of `len` bytes with `duplicates` repeated occurrences
+    /// is a marker.
+    pub fn accepts(self, len: usize, duplicates: usize) -> bool {
+        match self {
+            Tolerance::MinDistinct(distinct) => len - duplicates >= distinct,
+            Tolerance::MaxDuplicates(max) => duplicates <= max,
+        }
+    }
+}
+
+/// Like `find_marker`, accepting windows with some repeated bytes.
+pub fn find_tolerant_marker(signal: &[u8], len: usize, tolerance: Tolerance) -> Option<usize> {
+    if len == 0 {
+        return tolerance.accepts(0, 0).then_some(0);
+    }
+
+    let mut detector = MarkerDetector::with_tolerance(len, tolerance);
+    signal.iter().find_map(|&byte| detector.push(byte))
+}
+
+/// Way of looking for markers in a byte signal, all giving the same results.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Strategy {
+    /// Checks every window on its own with a `HashSet`.
+    HashSet,
+    /// `find_marker`.
+    SlidingWindow,
+    /// `find_marker_bitmask`.
+    Bitmask,
+    /// `find_marker_simd`.
+    Simd,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [
+        Strategy::HashSet,
+        Strategy::SlidingWindow,
+        Strategy::Bitmask,
+        Strategy::Simd,
+    ];
+
+    pub fn find_marker(self, signal: &[u8], len: usize) -> Option<usize> {
+        match self {
+            Strategy::HashSet if len == 0 => Some(0),
+            Strategy::HashSet => signal
+                .windows(len)
+                .position(|window| {
+                    let mut uniq = HashSet::new();
+                    window.iter().all(|x| uniq.insert(x))
+                })
+                .map(|idx| idx + len),
+            Strategy::SlidingWindow => find_marker(signal, len),
+            Strategy::Bitmask => find_marker_bitmask(signal, len),
+            Strategy::Simd => find_marker_simd(signal, len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use quickcheck::quickcheck;
+
+    use crate::marker::{
+        find_char_marker, find_grapheme_marker, find_marker, find_tolerant_marker,
+        get_first_different_sequence_index, Strategy, Tolerance,
+    };
+
+    #[test]
+    fn part_one_test_1() {
+        let s = "bvwbjplbgvbhsrlpgdmjqwftvncz";
+        let r = get_first_different_sequence_index(s, 4);
+
+        assert_eq!(r, Some(5));
+    }
+
+    #[test]
+    fn part_one_test_2() {
+        let s = "nppdvjthqldpwncqszvftbrmjlhg";
+        let r = get_first_different_sequence_index(s, 4);
+
+        assert_eq!(r, Some(6));
+    }
+
+    #[test]
+    fn part_one_test_3() {
+        let s = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
+        let r = get_first_different_sequence_index(s, 4);
+
+        assert_eq!(r, Some(10));
+    }
+
+    #[test]
+    fn part_one_test_4() {
+        let s = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
+        let r = get_first_different_sequence_index(s, 4);
+
+        assert_eq!(r, Some(11));
+    }
+
+    #[test]
+    fn part_two_test_1() {
+        let s = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        let r = get_first_different_sequence_index(s, 14);
+
+        assert_eq!(r, Some(19));
+    }
+
+    #[test]
+    fn part_two_test_2() {
+        let s = "bvwbjplbgvbhsrlpgdmjqwftvncz";
+        let r = get_first_different_sequence_index(s, 14);
+
+        assert_eq!(r, Some(23));
+    }
+
+    #[test]
+    fn part_two_test_3() {
+        let s = "nppdvjthqldpwncqszvftbrmjlhg";
+        let r = get_first_different_sequence_index(s, 14);
+
+        assert_eq!(r, Some(23));
+    }
+
+    #[test]
+    fn part_two_test_4() {
+        let s = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
+        let r = get_first_different_sequence_index(s, 14);
+
+        assert_eq!(r, Some(29));
+    }
+
+    #[test]
+    fn part_two_test_5() {
+        let s = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
+        let r = get_first_different_sequence_index(s, 14);
+
+        assert_eq!(r, Some(26));
+    }
+
+    #[test]
+    fn sliding_window_examples() {
+        let examples = [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+
+        for (signal, packet, message) in examples {
+            assert_eq!(find_marker(signal.as_bytes(), 4), Some(packet));
+            assert_eq!(find_marker(signal.as_bytes(), 14), Some(message));
+        }
+        assert_eq!(find_marker(b"abcabc", 4), None);
+        assert_eq!(find_marker(b"aabcd", 4), Some(5));
+    }
+
+    #[test]
+    fn zero_length_windows() {
+        assert_eq!(get_first_different_sequence_index("", 0), Some(0));
+        assert_eq!(find_marker(b"aa", 0), Some(0));
+        assert_eq!(find_char_marker("", 0), Some(0));
+    }
+
+    #[test]
+    fn whole_signal_window() {
+        assert_eq!(get_first_different_sequence_index("abc", 3), Some(3));
+        assert_eq!(get_first_different_sequence_index("abc", 4), None);
+        assert_eq!(find_marker(b"abc", 3), Some(3));
+    }
+
+    #[test]
+    fn non_ascii_text() {
+        let signal = "€€ab";
+
+        assert_eq!(get_first_different_sequence_index(signal, 3), Some(4));
+        assert_eq!(find_char_marker(signal, 3), Some(4));
+        // The three bytes encoding '€' are distinct.
+        assert_eq!(find_marker(signal.as_bytes(), 3), Some(3));
+
+        // 'e' followed by a combining acute accent is a single grapheme.
+        let signal = "e\u{301}ee\u{301}xe";
+        assert_eq!(find_char_marker(signal, 3), Some(6));
+        assert_eq!(find_grapheme_marker(signal, 3), Some(4));
+    }
+
+    #[test]
+    fn tolerant_markers() {
+        let signal = b"aabbccddcba";
+
+        assert_eq!(
+            find_tolerant_marker(signal, 4, Tolerance::MaxDuplicates(1)),
+            Some(5)
+        );
+        assert_eq!(
+            find_tolerant_marker(signal, 4, Tolerance::MinDistinct(3)),
+            Some(5)
+        );
+        assert_eq!(
+            find_tolerant_marker(signal, 5, Tolerance::MinDistinct(4)),
+            Some(11)
+        );
+        assert_eq!(
+            find_tolerant_marker(signal, 2, Tolerance::MinDistinct(3)),
+            None
+        );
+        assert_eq!(
+            find_tolerant_marker(signal, 0, Tolerance::MinDistinct(1)),
+            None
+        );
+        assert_eq!(
+            find_tolerant_marker(signal, 6, Tolerance::MaxDuplicates(6)),
+            Some(6)
+        );
+    }
+
+    quickcheck! {
+        fn same_as_hash_set(symbols: Vec<u8>, len: u8) -> bool {
+            let signal: String = symbols.iter().map(|s| char::from(b'a' + s % 8)).collect();
+            let len = usize::from(len % 9);
+            let expected = get_first_different_sequence_index(&signal, len);
+
+            find_marker(signal.as_bytes(), len) == expected
+                && find_char_marker(&signal, len) == expected
+                && find_grapheme_marker(&signal, len) == expected
+        }
+
+        fn no_tolerance(symbols: Vec<u8>, len: u8) -> bool {
+            let signal: Vec<u8> = symbols.iter().map(|s| b'a' + s % 8).collect();
+            let len = usize::from(len % 9);
+            let expected = find_marker(&signal, len);
+
+            find_tolerant_marker(&signal, len, Tolerance::MaxDuplicates(0)) == expected
+                && find_tolerant_marker(&signal, len, Tolerance::MinDistinct(len)) == expected
+        }
+
+        fn strategies_agree(symbols: Vec<u8>, alphabet: u8, len: u8) -> bool {
+            // Up to 100 different bytes, to go through the bitmask fallbacks too.
+            let signal: Vec<u8> = symbols.iter().map(|s| s % (1 + alphabet % 100)).collect();
+            let len = usize::from(len % 70);
+            let expected = Strategy::HashSet.find_marker(&signal, len);
+
+            Strategy::ALL.iter().all(|strategy| strategy.find_marker(&signal, len) == expected)
+        }
+    }
+
+    #[test]
+    fn strategies_on_long_signals() {
+        let examples = [
+            (b"bvwbjplbgvbhsrlpgdmjqwftvncz".repeat(40), 14, Some(23)),
+            (
+                [&b"abcd".repeat(300)[..], b"efghijklmnopqrstuvwxyzABCDEF"].concat(),
+                30,
+                Some(1226),
+            ),
+            (b"abcd".repeat(300), 5, None),
+            // Too many different bytes for a bitmask.
+            ((0..=255).collect::<Vec<u8>>().repeat(4), 100, Some(100)),
+            ((0..=255).collect::<Vec<u8>>().repeat(4), 64, Some(64)),
+            (
+                [&b"aab"[..], &(0..=255).collect::<Vec<u8>>()].concat(),
+                64,
+                Some(65),
+            ),
+            // The 65th different byte shows up before the marker.
+            (
+                (0..64)
+                    .flat_map(|byte| [byte, byte])
+                    .chain(200..=255)
+                    .collect(),
+                3,
+                Some(130),
+            ),
+            // A marker found before the 65th different byte shows up.
+            (
+                [&b"abcd"[..], &(0..=255).collect::<Vec<u8>>()].concat(),
+                4,
+                Some(4),
+            ),
+        ];
+
+        for (signal, len, expected) in examples {
+            for strategy in Strategy::ALL {
+                assert_eq!(
+                    strategy.find_marker(&signal, len),
+                    expected,
+                    "{:?}",
+                    strategy
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use std::{collections::VecDeque, io::Read};
+
+use crate::marker::Tolerance;
+
+/// Size of the chunks read from a `Read` at a time.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Incremental marker detection over a signal that arrives piece by piece.
+///
+/// Only the last `len` bytes are kept, in a ring buffer, along with how many
+/// times each byte occurs among them.
+#[derive(Debug, Clone)]
+pub struct MarkerDetector {
+    len: usize,
+    tolerance: Tolerance,
+    window: VecDeque<u8>,
+    counts: [usize; 256],
+    /// Occurrences in the window beyond the first one of each byte.
+    duplicates: usize,
+    /// Number of bytes pushed so far.
+    position: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(len: usize) -> Self {
+        Self::with_tolerance(len, Tolerance::MaxDuplicates(0))
+    }
+
+    /// Detector for windows of `len` bytes that are only mostly distinct.
+    pub fn with_tolerance(len: usize, tolerance: Tolerance) -> Self {
+        MarkerDetector {
+            len,
+            tolerance,
+            window: VecDeque::with_capacity(len + 1),
+            counts: [0; 256],
+            duplicates: 0,
+            position: 0,
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Adds the next byte of the signal. Returns the position right after it
+    /// if it ends a window of `len` distinct bytes (or close enough, with a
+    /// tolerance), which is always the case for empty windows.
+    pub fn push(&mut self, byte: u8) -> Option<usize> {
+        self.position += 1;
+
+        self.counts[usize::from(byte)] += 1;
+        if self.counts[usize::from(byte)] > 1 {
+            self.duplicates += 1;
+        }
+        self.window.push_back(byte);
+
+        if self.window.len() > self.len {
+            if let Some(old) = self.window.pop_front() {
+                if self.counts[usize::from(old)] > 1 {
+                    self.duplicates -= 1;
+                }
+                self.counts[usize::from(old)] -= 1;
+            }
+        }
+
+        (self.window.len() == self.len && self.tolerance.accepts(self.len, self.duplicates))
+            .then_some(self.position)
+    }
+
+    /// Adds a chunk of the signal, returning the end of every marker window
+    /// completed by it.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<usize> {
+        bytes.iter().filter_map(|&byte| self.push(byte)).collect()
+    }
+}
+
+/// Looks for the first marker of several lengths at once.
+///
+/// Keeps the start of the longest run of distinct bytes ending at the current
+/// position: the first marker of a length ends where the run first gets that
+/// long.
+#[derive(Debug, Clone)]
+pub struct MultiMarkerDetector {
+    lens: Vec<usize>,
+    found: Vec<Option<usize>>,
+    /// 1-based position of the last occurrence of each byte, 0 if not seen yet.
+    last_seen: [usize; 256],
+    start: usize,
+    position: usize,
+}
+
+impl MultiMarkerDetector {
+    pub fn new(lens: &[usize]) -> Self {
+        MultiMarkerDetector {
+            lens: lens.to_vec(),
+            found: lens.iter().map(|&len| (len == 0).then_some(0)).collect(),
+            last_seen: [0; 256],
+            start: 0,
+            position: 0,
+        }
+    }
+
+    pub fn push(&mut self, byte: u8) {
+        self.position += 1;
+        self.start = self.start.max(self.last_seen[usize::from(byte)]);
+        self.last_seen[usize::from(byte)] = self.position;
+
+        let run = self.position - self.start;
+        for (len, found) in self.lens.iter().zip(self.found.iter_mut()) {
+            if found.is_none() && *len <= run {
+                *found = Some(self.position);
+            }
+        }
+    }
+
+    /// Whether a marker was found for every length.
+    pub fn is_done(&self) -> bool {
+        self.found.iter().all(Option::is_some)
+    }
+
+    /// First marker for each length, in the order they were given.
+    pub fn found(&self) -> &[Option<usize>] {
+        &self.found
+    }
+}
+
+/// Like `find_marker`, consuming bytes only until the marker is complete.
+pub fn find_marker_in_iter(bytes: impl IntoIterator<Item = u8>, len: usize) -> Option<usize> {
+    markers(bytes, len).next()
+}
+
+/// End of every window of `len` distinct bytes, in order. Windows may overlap.
+pub fn markers(bytes: impl IntoIterator<Item = u8>, len: usize) -> impl Iterator<Item = usize> {
+    let mut detector = MarkerDetector::new(len);
+    // The detector only reports windows ending after a byte.
+    let before_any = (len == 0).then_some(0);
+
+    before_any.into_iter().chain(
+        bytes
+            .into_iter()
+            .filter_map(move |byte| detector.push(byte)),
+    )
+}
+
+/// First marker for each of `lens`, in the same order, in a single pass that
+/// stops once all of them are found.
+pub fn find_markers(bytes: impl IntoIterator<Item = u8>, lens: &[usize]) -> Vec<Option<usize>> {
+    let mut detector = MultiMarkerDetector::new(lens);
+
+    for byte in bytes {
+        if detector.is_done() {
+            break;
+        }
+        detector.push(byte);
+    }
+
+    detector.found
+}
+
+/// Reads the signal in chunks, handing each one to `on_chunk` until it
+/// returns true or the signal ends.
+fn read_chunks(
+    mut reader: impl Read,
+    mut on_chunk: impl FnMut(&[u8]) -> bool,
+) -> std::io::Result<()> {
+    let mut buf = [0; CHUNK_SIZE];
+
+    loop {
+        let read = match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+
+        if on_chunk(&buf[..read]) {
+            return Ok(());
+        }
+    }
+}
+
+/// Like `find_marker`, reading the signal in chunks and stopping with the
+/// chunk that completes the marker.
+pub fn read_marker(reader: impl Read, len: usize) -> std::io::Result<Option<usize>> {
+    if len == 0 {
+        return Ok(Some(0));
+    }
+
+    let mut detector = MarkerDetector::new(len);
+    let mut res = None;
+
+    read_chunks(reader, |chunk| {
+        res = chunk.iter().find_map(|&byte| detector.push(byte));
+        res.is_some()
+    })?;
+
+    Ok(res)
+}
+
+/// Like `find_markers`, reading the signal in chunks.
+pub fn read_markers(reader: impl Read, lens: &[usize]) -> std::io::Result<Vec<Option<usize>>> {
+    let mut detector = MultiMarkerDetector::new(lens);
+
+    read_chunks(reader, |chunk| {
+        for &byte in chunk {
+            if detector.is_done() {
+                break;
+            }
+            detector.push(byte);
+        }
+        detector.is_done()
+    })?;
+
+    Ok(detector.found)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use quickcheck::quickcheck;
+
+    use crate::{
+        marker::find_marker,
+        stream::{
+            find_marker_in_iter, find_markers, markers, read_marker, read_markers, MarkerDetector,
+        },
+    };
+
+    #[test]
+    fn pushed_in_pieces() {
+        let signal = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        let mut detector = MarkerDetector::new(4);
+
+        assert_eq!(detector.feed(&signal[..5]), []);
+        assert_eq!(detector.feed(&signal[5..9]), [7, 8, 9]);
+        assert_eq!(detector.position(), 9);
+    }
+
+    /// Reader for the part of a stream that should never be reached.
+    struct Unreachable;
+
+    impl Read for Unreachable {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("read past the marker"))
+        }
+    }
+
+    #[test]
+    fn stops_reading_at_the_marker() {
+        let signal = b"bvwbjplbgvbhsrlpgdmjqwftvncz";
+        let reader = (&signal[..]).chain(Unreachable);
+
+        assert_eq!(read_marker(reader, 4).unwrap(), Some(5));
+        assert_eq!(read_marker(&b"aaaa"[..], 2).unwrap(), None);
+    }
+
+    #[test]
+    fn every_marker() {
+        let found: Vec<usize> = markers(*b"abcabcaab", 3).collect();
+        assert_eq!(found, [3, 4, 5, 6, 7]);
+
+        assert_eq!(markers(*b"aaaa", 2).count(), 0);
+        assert_eq!(markers(*b"aa", 0).collect::<Vec<_>>(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn several_lengths_at_once() {
+        let signal = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+
+        assert_eq!(
+            find_markers(signal.iter().copied(), &[14, 4, 0, 31]),
+            [Some(19), Some(7), Some(0), None]
+        );
+        assert_eq!(
+            read_markers((&signal[..]).chain(Unreachable), &[4, 14]).unwrap(),
+            [Some(7), Some(19)]
+        );
+        assert_eq!(find_markers(signal.iter().copied(), &[]), []);
+    }
+
+    quickcheck! {
+        fn same_as_slices(symbols: Vec<u8>, len: u8) -> bool {
+            let signal: Vec<u8> = symbols.iter().map(|s| b'a' + s % 8).collect();
+            let len = usize::from(len % 9);
+            let expected = find_marker(&signal, len);
+
+            find_marker_in_iter(signal.iter().copied(), len) == expected
+                && read_marker(&signal[..], len).ok() == Some(expected)
+                && find_markers(signal.iter().copied(), &[len, 3]) == [expected, find_marker(&signal, 3)]
+        }
+    }
+}